@@ -0,0 +1,319 @@
+//! The align module matches the segments of multiple Runs, even if the runners named their splits
+//! differently or their routes add or remove splits. Segments are matched by the similarity of
+//! their names, while keeping their order intact.
+//!
+//! ```no_run
+//! # use splits_io_api::{align::Aligner, Run};
+//! # fn align(pb: &Run, rival: &Run) {
+//! let alignment = Aligner::new(&[pb, rival])
+//!     // "Forsaken Fortress 1" of the rival is actually "FF1 Skip" in the PB.
+//!     .with_match(1, 3, 2)
+//!     .align();
+//!
+//! for row in &alignment.rows {
+//!     println!("{:?} ({:.0}% confident)", row.segments, 100.0 * row.confidence);
+//! }
+//! # }
+//! ```
+
+use crate::Run;
+
+/// A manual correction of the automatic alignment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Override {
+    Match {
+        run: usize,
+        segment: usize,
+        reference_segment: usize,
+    },
+    NoMatch {
+        run: usize,
+        segment: usize,
+    },
+}
+
+/// Aligns the segments of multiple runs with the segments of the first run, which acts as the
+/// reference.
+#[derive(Debug, Clone)]
+pub struct Aligner<'a> {
+    runs: Vec<&'a Run>,
+    overrides: Vec<Override>,
+    min_similarity: f64,
+}
+
+/// A segment of a run that was matched with a segment of the reference run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SegmentMatch {
+    /// The position of the segment within the segments of its run.
+    pub index: usize,
+    /// How confident the match is, as a value between 0 and 1. Manual matches are always fully
+    /// confident.
+    pub confidence: f64,
+}
+
+/// The segments of all the runs that match a single segment of the reference run.
+#[derive(Debug, Clone)]
+pub struct AlignedSegment {
+    /// The matching segment of each run, in the order the runs were provided. The first entry is
+    /// always the reference segment itself.
+    pub segments: Vec<Option<SegmentMatch>>,
+    /// The confidence of the least confident match in this row.
+    pub confidence: f64,
+}
+
+/// The result of aligning the segments of multiple runs.
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    /// A row for each segment of the reference run.
+    pub rows: Vec<AlignedSegment>,
+    /// The positions of the segments of each run that don't match any segment of the reference
+    /// run.
+    pub unmatched: Vec<Vec<usize>>,
+}
+
+impl Alignment {
+    /// For each segment of the reference run, the position of the matching segment in the given
+    /// run.
+    pub fn matches(&self, run: usize) -> Vec<Option<usize>> {
+        self.rows
+            .iter()
+            .map(|row| Some(row.segments.get(run).copied()??.index))
+            .collect()
+    }
+}
+
+impl<'a> Aligner<'a> {
+    /// Creates an aligner for the given runs. The first run is the reference run that all the
+    /// other runs are aligned with.
+    pub fn new(runs: &[&'a Run]) -> Self {
+        Self {
+            runs: runs.to_vec(),
+            overrides: Vec::new(),
+            min_similarity: 0.5,
+        }
+    }
+
+    /// Sets how similar the names of two segments need to be at least, as a value between 0 and
+    /// 1, for them to be matched automatically. The default is 0.5.
+    pub const fn min_similarity(mut self, min_similarity: f64) -> Self {
+        self.min_similarity = min_similarity;
+        self
+    }
+
+    /// Manually matches a segment of a run with a segment of the reference run. The segments are
+    /// specified by their position within their runs. Manual matches that would change the order
+    /// of the segments are ignored.
+    pub fn with_match(mut self, run: usize, segment: usize, reference_segment: usize) -> Self {
+        self.overrides.push(Override::Match {
+            run,
+            segment,
+            reference_segment,
+        });
+        self
+    }
+
+    /// Manually prevents a segment of a run from being matched with any segment of the reference
+    /// run. The segment is specified by its position within the run.
+    pub fn without_match(mut self, run: usize, segment: usize) -> Self {
+        self.overrides.push(Override::NoMatch { run, segment });
+        self
+    }
+
+    /// Aligns the segments of all the runs with the reference run.
+    pub fn align(&self) -> Alignment {
+        let Some(reference) = self.runs.first() else {
+            return Alignment {
+                rows: Vec::new(),
+                unmatched: Vec::new(),
+            };
+        };
+
+        let mut rows = (0..reference.segments.len())
+            .map(|index| AlignedSegment {
+                segments: vec![Some(SegmentMatch {
+                    index,
+                    confidence: 1.0,
+                })],
+                confidence: 1.0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut unmatched = vec![Vec::new()];
+
+        for (run_index, run) in self.runs.iter().enumerate().skip(1) {
+            let matches = self.align_pair(reference, run, run_index);
+            let mut matched = vec![false; run.segments.len()];
+
+            for (row, segment_match) in rows.iter_mut().zip(matches) {
+                if let Some((index, confidence)) = segment_match {
+                    matched[index] = true;
+                    row.confidence = row.confidence.min(confidence);
+                    row.segments.push(Some(SegmentMatch { index, confidence }));
+                } else {
+                    row.segments.push(None);
+                }
+            }
+
+            unmatched.push(
+                matched
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &matched)| !matched)
+                    .map(|(index, _)| index)
+                    .collect(),
+            );
+        }
+
+        Alignment { rows, unmatched }
+    }
+
+    /// Finds the order preserving matching with the highest total similarity between the segments
+    /// of the reference run and the given run.
+    fn align_pair(
+        &self,
+        reference: &Run,
+        run: &Run,
+        run_index: usize,
+    ) -> Vec<Option<(usize, f64)>> {
+        let (n, m) = (reference.segments.len(), run.segments.len());
+        let reference_names = normalized_names(reference);
+        let names = normalized_names(run);
+
+        // The score of matching each pair of segments, if they are allowed to be matched.
+        let mut scores = vec![vec![None; m]; n];
+        for (i, reference_name) in reference_names.iter().enumerate() {
+            for (j, name) in names.iter().enumerate() {
+                let similarity = similarity(reference_name, name);
+                if similarity >= self.min_similarity {
+                    let position = 1.0 - (relative(i, n) - relative(j, m)).abs();
+                    scores[i][j] = Some(0.8 * similarity + 0.2 * position);
+                }
+            }
+        }
+
+        let mut forced = Vec::new();
+        for &over in &self.overrides {
+            match over {
+                Override::NoMatch { run, segment } if run == run_index && segment < m => {
+                    scores.iter_mut().for_each(|row| row[segment] = None);
+                }
+                Override::Match {
+                    run,
+                    segment,
+                    reference_segment,
+                } if run == run_index && segment < m && reference_segment < n => {
+                    scores.iter_mut().for_each(|row| row[segment] = None);
+                    scores[reference_segment].fill(None);
+                    forced.push((reference_segment, segment));
+                }
+                _ => {}
+            }
+        }
+        // Manual matches outweigh any number of automatic matches.
+        let forced_score = (n + m + 1) as f64;
+        for &(i, j) in &forced {
+            scores[i][j] = Some(forced_score);
+        }
+
+        let mut best = vec![vec![0.0f64; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                let skip = best[i + 1][j].max(best[i][j + 1]);
+                best[i][j] = match scores[i][j] {
+                    Some(score) => skip.max(best[i + 1][j + 1] + score),
+                    None => skip,
+                };
+            }
+        }
+
+        let mut matches = vec![None; n];
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            match scores[i][j] {
+                Some(score) if best[i][j] == best[i + 1][j + 1] + score => {
+                    let confidence = if forced.contains(&(i, j)) { 1.0 } else { score };
+                    matches[i] = Some((j, confidence));
+                    i += 1;
+                    j += 1;
+                }
+                _ if best[i][j] == best[i + 1][j] => i += 1,
+                _ => j += 1,
+            }
+        }
+        matches
+    }
+}
+
+fn relative(index: usize, len: usize) -> f64 {
+    if len > 1 {
+        index as f64 / (len - 1) as f64
+    } else {
+        0.0
+    }
+}
+
+/// Lowercases the names and splits them into words of letters and digits.
+fn normalized_names(run: &Run) -> Vec<Vec<String>> {
+    run.segments
+        .iter()
+        .map(|segment| {
+            segment
+                .name
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect()
+        })
+        .collect()
+}
+
+/// Calculates how similar two names are as a value between 0 and 1, based on their normalized
+/// edit distance. A name that is an abbreviation of the other, such as "FF1" for "Forsaken
+/// Fortress 1", is considered very similar too.
+fn similarity(a: &[String], b: &[String]) -> f64 {
+    let (joined_a, joined_b) = (a.join(" "), b.join(" "));
+    let max_len = joined_a.chars().count().max(joined_b.chars().count());
+    let edit_similarity = if max_len == 0 {
+        1.0
+    } else {
+        1.0 - levenshtein(&joined_a, &joined_b) as f64 / max_len as f64
+    };
+
+    let is_abbreviation =
+        |short: &[String], long: &[String]| long.len() > 1 && short.concat() == abbreviate(long);
+    if is_abbreviation(a, b) || is_abbreviation(b, a) {
+        edit_similarity.max(0.9)
+    } else {
+        edit_similarity
+    }
+}
+
+/// Abbreviates the words by their first letter, keeping numbers intact.
+fn abbreviate(words: &[String]) -> String {
+    words
+        .iter()
+        .flat_map(|word| {
+            let take = if word.chars().all(|c| c.is_ascii_digit()) {
+                word.len()
+            } else {
+                1
+            };
+            word.chars().take(take)
+        })
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != b);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(previous + 1).min(distances[j] + 1);
+        }
+    }
+    distances[b.len()]
+}
@@ -0,0 +1,219 @@
+//! The bulk module handles uploading many splits files at once. The progress of a bulk upload is
+//! recorded in a [`Manifest`], which can be persisted to disk so that an interrupted upload can be
+//! resumed without uploading any of the files again.
+//!
+//! The files and the manifest are accessed with blocking file system calls, as the crate doesn't
+//! depend on an async runtime. These are short reads and writes of local files, but on a runtime
+//! with few worker threads they briefly block the thread that polls the upload.
+
+use crate::{
+    run::{self, UploadedRun},
+    Client,
+};
+use futures_util::stream::{self, StreamExt};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The outcome of uploading a single file.
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ManifestEntry {
+    /// The file was successfully uploaded.
+    Uploaded {
+        /// The unique ID for identifying the uploaded run.
+        id: Box<str>,
+        /// The token that can be used by the user to claim the run as their own.
+        claim_token: Box<str>,
+    },
+    /// The file could not be uploaded. It is going to be uploaded again when resuming.
+    Failed {
+        /// The reason why the upload failed.
+        error: Box<str>,
+    },
+}
+
+/// Keeps track of which files of a bulk upload have already been uploaded.
+#[derive(Debug, Default, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct Manifest {
+    /// The outcome of the upload for each of the files, keyed by their path.
+    pub entries: BTreeMap<PathBuf, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Loads a manifest from the given path. If there is no file at the path yet, an empty
+    /// manifest is returned.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stores the manifest at the given path. The manifest is first written to a temporary file
+    /// next to it, so an interruption never leaves a partially written manifest behind.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let temp_path = temp_path(path);
+        fs::write(&temp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&temp_path, path)
+    }
+
+    /// Checks whether the file at the given path was already uploaded successfully.
+    pub fn is_uploaded(&self, path: &Path) -> bool {
+        matches!(self.entries.get(path), Some(ManifestEntry::Uploaded { .. }))
+    }
+
+    /// Iterates over all the files that were uploaded successfully.
+    pub fn uploaded(&self) -> impl Iterator<Item = (&Path, UploadedRun)> + '_ {
+        self.entries.iter().filter_map(|(path, entry)| match entry {
+            ManifestEntry::Uploaded { id, claim_token } => Some((
+                &**path,
+                UploadedRun {
+                    id: id.clone(),
+                    claim_token: claim_token.clone(),
+                },
+            )),
+            ManifestEntry::Failed { .. } => None,
+        })
+    }
+
+    /// Iterates over all the files that failed to upload, along with the reason why.
+    pub fn failed(&self) -> impl Iterator<Item = (&Path, &str)> + '_ {
+        self.entries.iter().filter_map(|(path, entry)| match entry {
+            ManifestEntry::Failed { error } => Some((&**path, &**error)),
+            ManifestEntry::Uploaded { .. } => None,
+        })
+    }
+}
+
+/// The settings for a bulk upload.
+pub struct Settings<'a> {
+    /// The maximum amount of uploads that are in flight at the same time.
+    pub concurrency: usize,
+    /// The path of the manifest to resume from. It is updated after every few finished uploads and
+    /// once the bulk upload is done. If this is `None`, every file is uploaded and the manifest is
+    /// only returned.
+    pub manifest: Option<&'a Path>,
+}
+
+impl Default for Settings<'_> {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            manifest: None,
+        }
+    }
+}
+
+/// How many uploads finish between saves of the manifest. The manifest is saved once more when the
+/// bulk upload is done.
+const SAVE_INTERVAL: usize = 32;
+
+/// Uploads all the files in the directory and its subdirectories to splits.io. Failing to upload
+/// individual files doesn't stop the bulk upload. Instead the error is recorded in the manifest.
+/// An error is only returned if the directory or the manifest can't be accessed. The manifest
+/// isn't uploaded if it's stored in the directory.
+pub async fn upload_dir(
+    client: &Client,
+    dir: &Path,
+    settings: Settings<'_>,
+) -> io::Result<Manifest> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+
+    let excluded = settings
+        .manifest
+        .into_iter()
+        .flat_map(|path| [path.to_owned(), temp_path(path)])
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect::<Vec<_>>();
+    files.retain(|file| fs::canonicalize(file).map_or(true, |file| !excluded.contains(&file)));
+
+    files.sort_unstable();
+    upload_files(client, files, settings).await
+}
+
+/// Uploads all the files to splits.io. Failing to upload individual files doesn't stop the bulk
+/// upload. Instead the error is recorded in the manifest. An error is only returned if the
+/// manifest can't be accessed. The manifest is saved after every few uploads, so if the bulk
+/// upload is interrupted, the files of the last few uploads are uploaded again when resuming.
+pub async fn upload_files<I>(
+    client: &Client,
+    files: I,
+    settings: Settings<'_>,
+) -> io::Result<Manifest>
+where
+    I: IntoIterator<Item = PathBuf>,
+{
+    let mut manifest = match settings.manifest {
+        Some(path) => Manifest::load(path)?,
+        None => Manifest::default(),
+    };
+
+    let pending = files
+        .into_iter()
+        .filter(|path| !manifest.is_uploaded(path))
+        .collect::<Vec<_>>();
+
+    let mut uploads = stream::iter(pending)
+        .map(|path| async move {
+            let entry = match upload_file(client, &path).await {
+                Ok(UploadedRun { id, claim_token }) => ManifestEntry::Uploaded { id, claim_token },
+                Err(error) => ManifestEntry::Failed { error },
+            };
+            (path, entry)
+        })
+        .buffer_unordered(settings.concurrency.max(1));
+
+    let mut unsaved = 0;
+    while let Some((path, entry)) = uploads.next().await {
+        manifest.entries.insert(path, entry);
+        unsaved += 1;
+        if unsaved == SAVE_INTERVAL {
+            save_manifest(&manifest, settings.manifest)?;
+            unsaved = 0;
+        }
+    }
+    if unsaved != 0 {
+        save_manifest(&manifest, settings.manifest)?;
+    }
+
+    Ok(manifest)
+}
+
+fn save_manifest(manifest: &Manifest, path: Option<&Path>) -> io::Result<()> {
+    match path {
+        Some(path) => manifest.save(path),
+        None => Ok(()),
+    }
+}
+
+async fn upload_file(client: &Client, path: &Path) -> Result<UploadedRun, Box<str>> {
+    let run = fs::read(path).map_err(|e| e.to_string())?;
+    run::upload(client, run)
+        .await
+        .map_err(|e| e.to_string().into())
+}
+
+/// The path of the temporary file the manifest at the path is first written to.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    temp_path.into()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
@@ -5,9 +5,13 @@
 
 use crate::{
     get_json,
+    leaderboard::Leaderboard,
+    page::{self, Page, PageSettings},
+    query::RunQuery,
     wrapper::{ContainsCategory, ContainsRunners, ContainsRuns},
-    Category, Client, Error, Run, Runner,
+    Category, Client, Error, Run, Runner, TimingMethod,
 };
+use futures_util::{Stream, TryStreamExt};
 use reqwest::Url;
 
 impl Category {
@@ -25,11 +29,42 @@ impl Category {
     pub async fn runs(&self, client: &Client) -> Result<Vec<Run>, Error> {
         get_runs(client, &self.id).await
     }
+
+    /// Gets the Runs that belong to the Category. All the pages of the Runs are retrieved, one
+    /// after another.
+    pub async fn runs_all(&self, client: &Client) -> Result<Vec<Run>, Error> {
+        get_runs_all(client, &self.id).await
+    }
+
+    /// Gets the Runs that belong to the Category and match the query.
+    pub async fn query_runs(&self, client: &Client, query: &RunQuery) -> Result<Vec<Run>, Error> {
+        query_runs(client, &self.id, query).await
+    }
+
+    /// Gets the leaderboard of the Category, which ranks the fastest Run of each runner by the
+    /// given timing method.
+    pub async fn leaderboard(
+        &self,
+        client: &Client,
+        method: TimingMethod,
+    ) -> Result<Leaderboard, Error> {
+        get_leaderboard(client, &self.id, method).await
+    }
+
+    /// Streams the Runs that belong to the Category. The Runs are requested one page at a time as
+    /// they are needed.
+    pub fn runs_stream<'a>(
+        &self,
+        client: &'a Client,
+        settings: PageSettings,
+    ) -> impl Stream<Item = Result<Run, Error>> + 'a {
+        get_runs_stream(client, &self.id, settings)
+    }
 }
 
 /// Gets a Category.
 pub async fn get(client: &Client, id: &str) -> Result<Category, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/categories").unwrap();
+    let mut url = client.api_url("categories");
     url.path_segments_mut().unwrap().push(id);
 
     let ContainsCategory { category } = get_json(client, client.client.get(url)).await?;
@@ -39,7 +74,7 @@ pub async fn get(client: &Client, id: &str) -> Result<Category, Error> {
 
 /// Gets the Runners that belong to a Category.
 pub async fn get_runners(client: &Client, id: &str) -> Result<Vec<Runner>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/categories").unwrap();
+    let mut url = client.api_url("categories");
     url.path_segments_mut().unwrap().extend(&[id, "runners"]);
 
     let ContainsRunners { runners } = get_json(client, client.client.get(url)).await?;
@@ -49,10 +84,70 @@ pub async fn get_runners(client: &Client, id: &str) -> Result<Vec<Runner>, Error
 
 /// Gets the Runs that belong to a Category.
 pub async fn get_runs(client: &Client, id: &str) -> Result<Vec<Run>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/categories").unwrap();
-    url.path_segments_mut().unwrap().extend(&[id, "runs"]);
-
-    let ContainsRuns { runs } = get_json(client, client.client.get(url)).await?;
+    let ContainsRuns { runs } = get_json(client, client.client.get(runs_url(client, id))).await?;
 
     Ok(runs)
 }
+
+/// Gets the Runs that belong to a Category. All the pages of the Runs are retrieved, one after
+/// another.
+pub async fn get_runs_all(client: &Client, id: &str) -> Result<Vec<Run>, Error> {
+    get_runs_stream(client, id, PageSettings::default())
+        .try_collect()
+        .await
+}
+
+/// Gets a single page of the Runs that belong to a Category.
+pub async fn get_runs_page(
+    client: &Client,
+    id: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Run>, Error> {
+    page::get_page(
+        client,
+        runs_url(client, id),
+        page,
+        per_page,
+        |r: ContainsRuns| r.runs,
+    )
+    .await
+}
+
+/// Streams the Runs that belong to a Category. The Runs are requested one page at a time as they
+/// are needed.
+pub fn get_runs_stream<'a>(
+    client: &'a Client,
+    id: &str,
+    settings: PageSettings,
+) -> impl Stream<Item = Result<Run, Error>> + 'a {
+    page::stream(client, runs_url(client, id), settings, |r: ContainsRuns| {
+        r.runs
+    })
+}
+
+/// Gets the Runs that belong to a Category and match the query.
+pub async fn query_runs(client: &Client, id: &str, query: &RunQuery) -> Result<Vec<Run>, Error> {
+    query
+        .collect(get_runs_stream(client, id, query.page_settings()))
+        .await
+}
+
+/// Gets the leaderboard of a Category, which ranks the fastest Run of each runner by the given
+/// timing method.
+pub async fn get_leaderboard(
+    client: &Client,
+    id: &str,
+    method: TimingMethod,
+) -> Result<Leaderboard, Error> {
+    Ok(Leaderboard::from_runs(
+        get_runs_all(client, id).await?,
+        method,
+    ))
+}
+
+fn runs_url(client: &Client, id: &str) -> Url {
+    let mut url = client.api_url("categories");
+    url.path_segments_mut().unwrap().extend(&[id, "runs"]);
+    url
+}
@@ -0,0 +1,171 @@
+//! The category names module recognizes Categories of a Game that are the same ruleset under
+//! different names, such as `Any%`, `any%` and `Any %`. Names are normalized first and then
+//! resolved through alias tables, which can be configured for all games or for a single game.
+//!
+//! ```no_run
+//! # use splits_io_api::{category_names::CategoryNames, Client, Game, TimingMethod};
+//! # async fn merge(client: &Client, game: &Game) -> Result<(), splits_io_api::Error> {
+//! let names = CategoryNames::new().game_alias("sms", "Any% (No SRM)", "Any%");
+//! let categories = game.categories(client).await?;
+//!
+//! for group in names.group(game, categories) {
+//!     let leaderboard = group.leaderboard(client, TimingMethod::RealTime).await?;
+//!     println!("{}: {} runners", group.name, leaderboard.entries.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{category, leaderboard::Leaderboard, Category, Client, Error, Game, TimingMethod};
+use std::collections::{BTreeMap, HashMap};
+
+/// The alias tables for resolving category names. The tables can be loaded from a configuration
+/// file by deserializing them.
+///
+/// The aliases of a game take precedence over the aliases of all games, and the aliases by the
+/// game's shortened title over the ones by its ID. If multiple aliases of the same table
+/// normalize to the same name, the alias that sorts first is used.
+#[derive(Debug, Clone, Default, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct CategoryNames {
+    /// Aliases that apply to the categories of all games, from the alias to the name it means.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Aliases that only apply to the categories of a single game, by the shortened title or ID of
+    /// the game.
+    #[serde(default)]
+    pub game_aliases: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Categories of a Game that are the same ruleset.
+#[derive(Debug, Clone)]
+pub struct CategoryGroup {
+    /// The normalized name that identifies the group.
+    pub key: String,
+    /// The name to show for the group. This is the name the aliases resolve to, or the name of
+    /// the first category in the group if no alias applied.
+    pub name: String,
+    /// The categories in the group.
+    pub categories: Vec<Category>,
+}
+
+impl CategoryNames {
+    /// Creates alias tables without any aliases, so only the normalization of the names is used.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an alias that applies to the categories of all games.
+    pub fn alias(mut self, alias: &str, name: &str) -> Self {
+        self.aliases.insert(alias.into(), name.into());
+        self
+    }
+
+    /// Adds an alias that only applies to the categories of the game with the given shortened
+    /// title or ID.
+    pub fn game_alias(mut self, game: &str, alias: &str, name: &str) -> Self {
+        self.game_aliases
+            .entry(game.into())
+            .or_default()
+            .insert(alias.into(), name.into());
+        self
+    }
+
+    /// Resolves the name of a category of the game to the key of the group it belongs to.
+    pub fn key(&self, game: Option<&Game>, name: &str) -> String {
+        self.resolve(game, name).0
+    }
+
+    /// Whether the two names of categories of the game are the same ruleset.
+    pub fn same_category(&self, game: Option<&Game>, a: &str, b: &str) -> bool {
+        self.key(game, a) == self.key(game, b)
+    }
+
+    /// Groups the categories of the game that are the same ruleset, in the order their first
+    /// category appears.
+    pub fn group(
+        &self,
+        game: &Game,
+        categories: impl IntoIterator<Item = Category>,
+    ) -> Vec<CategoryGroup> {
+        let mut groups = Vec::<CategoryGroup>::new();
+        let mut positions = HashMap::<String, usize>::new();
+        for category in categories {
+            let (key, alias) = self.resolve(Some(game), &category.name);
+            match positions.get(&key) {
+                Some(&position) => groups[position].categories.push(category),
+                None => {
+                    positions.insert(key.clone(), groups.len());
+                    groups.push(CategoryGroup {
+                        key,
+                        name: alias.unwrap_or_else(|| category.name.trim().to_owned()),
+                        categories: vec![category],
+                    });
+                }
+            }
+        }
+        groups
+    }
+
+    /// Resolves the name to its key and the name its aliases resolve to. Aliases are followed
+    /// until a name without an alias is reached, so aliases can refer to other aliases.
+    fn resolve(&self, game: Option<&Game>, name: &str) -> (String, Option<String>) {
+        let tables = game
+            .into_iter()
+            .flat_map(|game| [game.shortname.as_deref(), Some(&*game.id)])
+            .flatten()
+            .filter_map(|game| self.game_aliases.get(game))
+            .chain([&self.aliases])
+            .collect::<Vec<_>>();
+
+        let mut key = normalize(name);
+        let mut resolved = None::<&String>;
+        // Limits how many aliases are followed, in case they form a cycle.
+        for _ in 0..8 {
+            let alias = tables.iter().find_map(|aliases| {
+                aliases
+                    .iter()
+                    .find(|(alias, _)| normalize(alias) == key)
+                    .map(|(_, name)| name)
+            });
+            match alias {
+                Some(name) if normalize(name) != key => {
+                    key = normalize(name);
+                    resolved = Some(name);
+                }
+                _ => break,
+            }
+        }
+        (key, resolved.cloned())
+    }
+}
+
+impl CategoryGroup {
+    /// The IDs of the categories in the group.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.categories.iter().map(|category| &*category.id)
+    }
+
+    /// Gets the leaderboard of all the categories in the group together, which ranks the fastest
+    /// Run of each runner across the categories by the given timing method.
+    pub async fn leaderboard(
+        &self,
+        client: &Client,
+        method: TimingMethod,
+    ) -> Result<Leaderboard, Error> {
+        let mut runs = Vec::new();
+        for category in &self.categories {
+            runs.extend(category::get_runs_all(client, &category.id).await?);
+        }
+        Ok(Leaderboard::from_runs(runs, method))
+    }
+}
+
+/// Normalizes a category name, so that names that only differ in case, whitespace or how the
+/// percent sign is written are the same.
+pub fn normalize(name: &str) -> String {
+    let name = name.to_lowercase().replace("percent", "%");
+    name.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '_'))
+        .map(|c| if c == '\u{FF05}' { '%' } else { c })
+        .collect()
+}
@@ -0,0 +1,175 @@
+//! The chapters module marks the splits of a Run within a video of the run, such as the VOD linked
+//! in its `video_url`. The timestamps can be written as YouTube chapters for a video's description
+//! or as WebVTT and SRT subtitles.
+//!
+//! ```no_run
+//! # use splits_io_api::{chapters, Client, Run};
+//! # async fn annotate(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let run = Run::get(client, "4cg", false).await?;
+//!
+//! // The timer started 42 seconds into the video.
+//! let mut description = Vec::new();
+//! chapters::write_youtube_chapters(&run, 42_000.0, &mut description)?;
+//! println!("{}", String::from_utf8(description)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Run, TimingMethod};
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// A part of a video that shows a segment of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// The time in milliseconds within the video where the segment starts.
+    pub start_ms: f64,
+    /// The time in milliseconds within the video where the segment ends.
+    pub end_ms: f64,
+}
+
+impl Run {
+    /// Determines where each segment is shown in a video of the run. The offset is the time in
+    /// milliseconds within the video when the timer started. It may be negative if the video
+    /// starts after the timer. Skipped segments are included in the chapter of the segment that
+    /// follows them, and chapters that end before the video starts are left out. Videos always
+    /// show real time, so the game time of the run isn't used.
+    pub fn chapters(&self, offset_ms: f64) -> Vec<Chapter> {
+        let mut start_ms = 0.0;
+        self.segments
+            .iter()
+            .filter(|segment| !segment.is_skipped(TimingMethod::RealTime))
+            .filter_map(|segment| {
+                let end_ms = segment.end_ms(TimingMethod::RealTime)?;
+                let chapter = Chapter {
+                    name: segment.name.clone(),
+                    start_ms: (start_ms + offset_ms).max(0.0),
+                    end_ms: end_ms + offset_ms,
+                };
+                start_ms = end_ms;
+                (chapter.end_ms > 0.0).then_some(chapter)
+            })
+            .collect()
+    }
+}
+
+/// Writes the segments of the run as YouTube chapters, with a line for each segment such as
+/// `1:02:03 Castle`. The offset is the time in milliseconds within the video when the timer
+/// started. YouTube requires the first chapter to start at the beginning of the video, so an
+/// `Intro` chapter is added if the timer started later than that.
+pub fn write_youtube_chapters<W: Write>(
+    run: &Run,
+    offset_ms: f64,
+    mut writer: W,
+) -> io::Result<()> {
+    let chapters = run.chapters(offset_ms);
+    if chapters
+        .first()
+        .is_some_and(|chapter| chapter.start_ms >= 1000.0)
+    {
+        writeln!(writer, "0:00 Intro")?;
+    }
+    for chapter in &chapters {
+        writeln!(
+            writer,
+            "{} {}",
+            ChapterTime(chapter.start_ms),
+            chapter.name.trim()
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the segments of the run as WebVTT subtitles, with a cue showing the name of each segment
+/// for as long as it is being run. The offset is the time in milliseconds within the video when
+/// the timer started.
+pub fn write_webvtt<W: Write>(run: &Run, offset_ms: f64, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "WEBVTT")?;
+    for chapter in run.chapters(offset_ms) {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "{} --> {}",
+            SubtitleTime(chapter.start_ms, '.'),
+            SubtitleTime(chapter.end_ms, '.'),
+        )?;
+        writeln!(writer, "{}", WebVttText(&chapter.name))?;
+    }
+    Ok(())
+}
+
+/// Writes the segments of the run as SRT subtitles, with a cue showing the name of each segment
+/// for as long as it is being run. The offset is the time in milliseconds within the video when
+/// the timer started.
+pub fn write_srt<W: Write>(run: &Run, offset_ms: f64, mut writer: W) -> io::Result<()> {
+    for (index, chapter) in run.chapters(offset_ms).iter().enumerate() {
+        if index != 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{}", index + 1)?;
+        writeln!(
+            writer,
+            "{} --> {}",
+            SubtitleTime(chapter.start_ms, ','),
+            SubtitleTime(chapter.end_ms, ','),
+        )?;
+        // An empty line would end the cue early.
+        writeln!(writer, "{}", chapter.name.trim().replace(['\r', '\n'], " "))?;
+    }
+    Ok(())
+}
+
+/// Formats a time in milliseconds the way YouTube expects chapters, such as `1:02:03` or `2:03`.
+struct ChapterTime(f64);
+
+impl fmt::Display for ChapterTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total_seconds = (self.0.max(0.0) / 1000.0) as u64;
+        let (hours, minutes) = (total_seconds / 3600, total_seconds / 60 % 60);
+        let seconds = total_seconds % 60;
+        if hours > 0 {
+            write!(f, "{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            write!(f, "{minutes}:{seconds:02}")
+        }
+    }
+}
+
+/// Formats a time in milliseconds the way subtitles store it, such as `01:02:03.456`, with the
+/// given separator before the milliseconds.
+struct SubtitleTime(f64, char);
+
+impl fmt::Display for SubtitleTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total_ms = self.0.max(0.0).round() as u64;
+        let (hours, minutes) = (total_ms / 3_600_000, total_ms / 60_000 % 60);
+        let (seconds, millis) = (total_ms / 1000 % 60, total_ms % 1000);
+        write!(
+            f,
+            "{hours:02}:{minutes:02}:{seconds:02}{}{millis:03}",
+            self.1
+        )
+    }
+}
+
+/// Escapes the characters that have a special meaning in the text of a WebVTT cue.
+struct WebVttText<'a>(&'a str);
+
+impl fmt::Display for WebVttText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.trim().chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '\n' | '\r' => f.write_str(" ")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
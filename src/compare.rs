@@ -0,0 +1,212 @@
+//! The compare module compares two Runs segment by segment, such as a runner's personal best and
+//! the run of a rival in the same category.
+
+use crate::{
+    align::{Aligner, Alignment},
+    Run, Segment, TimingMethod,
+};
+
+/// How a segment's duration was recorded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SegmentStatus {
+    /// The segment was recorded normally.
+    Normal,
+    /// The segment was skipped, so its duration is rolled into the following segment.
+    Skipped,
+    /// The segment's duration includes the previous segments that were skipped.
+    Reduced,
+}
+
+impl SegmentStatus {
+    const fn of(segment: &Segment, method: TimingMethod) -> Self {
+        if segment.is_skipped(method) {
+            SegmentStatus::Skipped
+        } else if segment.is_reduced(method) {
+            SegmentStatus::Reduced
+        } else {
+            SegmentStatus::Normal
+        }
+    }
+}
+
+/// The difference between a segment of a run and the matching segment of another run. A negative
+/// delta means that the run is faster than the other run.
+#[derive(Debug, Clone)]
+pub struct SegmentDelta {
+    /// The index of the segment within the run.
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// How the segment was recorded in the run.
+    pub status: SegmentStatus,
+    /// The index of the matching segment within the other run, if there is one.
+    pub other_segment_number: Option<u32>,
+    /// How the matching segment was recorded in the other run, if there is one.
+    pub other_status: Option<SegmentStatus>,
+    /// The difference in duration in milliseconds. If the segment is reduced, the durations
+    /// covering all the skipped segments before it are compared instead. This is `None` if the
+    /// segment was skipped in the run or there is no matching segment.
+    pub segment_delta_ms: Option<f64>,
+    /// The difference in split time at the end of the segment in milliseconds. This is `None` if
+    /// the segment was skipped in either run or there is no matching segment.
+    pub cumulative_delta_ms: Option<f64>,
+    /// The difference in best segment in milliseconds.
+    pub gold_delta_ms: Option<f64>,
+    /// The time in milliseconds that could be saved on the segment if it was as fast as the faster
+    /// of the two best segments. This is `None` for skipped and reduced segments, as their
+    /// durations don't correspond to the segment itself.
+    pub possible_time_save_ms: Option<f64>,
+}
+
+/// The segment by segment comparison of two runs.
+#[derive(Debug, Clone)]
+pub struct RunComparison {
+    /// The timing method the runs were compared with.
+    pub method: TimingMethod,
+    /// The deltas for each segment of the run.
+    pub segments: Vec<SegmentDelta>,
+    /// The indices of the segments of the other run that don't match any segment of the run.
+    pub unmatched_other_segments: Vec<u32>,
+    /// The difference in duration of the whole runs in milliseconds.
+    pub final_delta_ms: Option<f64>,
+}
+
+impl Run {
+    /// Compares the run segment by segment with another run. If both runs have the same number of
+    /// segments, they are matched by their position. Otherwise they are matched by the similarity
+    /// of their names while keeping their order, as done by the [`Aligner`].
+    pub fn compare(&self, other: &Run, method: TimingMethod) -> RunComparison {
+        let matches = match_segments(self, other);
+        compare_matched(self, other, &matches, method)
+    }
+
+    /// Compares the run segment by segment with another run, matching the segments according to
+    /// the given alignment. The run needs to be the reference run of the alignment, while the
+    /// other run is found at the given index of the aligned runs.
+    pub fn compare_aligned(
+        &self,
+        other: &Run,
+        alignment: &Alignment,
+        other_index: usize,
+        method: TimingMethod,
+    ) -> RunComparison {
+        let mut matches = alignment.matches(other_index);
+        matches.resize(self.segments.len(), None);
+        compare_matched(self, other, &matches, method)
+    }
+}
+
+fn compare_matched(
+    run: &Run,
+    other: &Run,
+    matches: &[Option<usize>],
+    method: TimingMethod,
+) -> RunComparison {
+    let matched_other = |index: usize| Some(&other.segments[matches[index]?]);
+
+    let segments = run
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let status = SegmentStatus::of(segment, method);
+            let other_segment = matched_other(index);
+            let other_status = other_segment.map(|other| SegmentStatus::of(other, method));
+
+            // A reduced segment covers all the skipped segments before it.
+            let span_start = run.segments[..index]
+                .iter()
+                .rposition(|segment| !segment.is_skipped(method))
+                .map_or(0, |previous| previous + 1);
+
+            let segment_delta_ms = if status == SegmentStatus::Skipped {
+                None
+            } else {
+                difference(
+                    span_duration(Some(&run.segments[span_start]), Some(segment), method),
+                    span_duration(matched_other(span_start), other_segment, method),
+                )
+            };
+
+            let cumulative_delta_ms = if status == SegmentStatus::Skipped
+                || other_status == Some(SegmentStatus::Skipped)
+            {
+                None
+            } else {
+                difference(
+                    segment.end_ms(method),
+                    other_segment.and_then(|other| other.end_ms(method)),
+                )
+            };
+
+            let gold_delta_ms = difference(
+                segment.shortest_duration_ms(method),
+                other_segment.and_then(|other| other.shortest_duration_ms(method)),
+            );
+
+            let possible_time_save_ms = if status == SegmentStatus::Normal {
+                segment
+                    .duration_ms(method)
+                    .zip(segment.shortest_duration_ms(method))
+                    .map(|(duration, own_best)| {
+                        let best = other_segment
+                            .and_then(|other| other.shortest_duration_ms(method))
+                            .map_or(own_best, |other_best| own_best.min(other_best));
+                        (duration - best).max(0.0)
+                    })
+            } else {
+                None
+            };
+
+            SegmentDelta {
+                segment_number: segment.segment_number,
+                name: segment.name.clone(),
+                status,
+                other_segment_number: other_segment.map(|other| other.segment_number),
+                other_status,
+                segment_delta_ms,
+                cumulative_delta_ms,
+                gold_delta_ms,
+                possible_time_save_ms,
+            }
+        })
+        .collect();
+
+    let unmatched_other_segments = other
+        .segments
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| !matches.contains(&Some(index)))
+        .map(|(_, segment)| segment.segment_number)
+        .collect();
+
+    let final_delta_ms = difference(run.duration_ms(method), other.duration_ms(method));
+
+    RunComparison {
+        method,
+        segments,
+        unmatched_other_segments,
+        final_delta_ms,
+    }
+}
+
+fn difference(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    Some(a? - b?)
+}
+
+/// The duration from the start of the first segment to the end of the last segment.
+fn span_duration(
+    first: Option<&Segment>,
+    last: Option<&Segment>,
+    method: TimingMethod,
+) -> Option<f64> {
+    difference(last?.end_ms(method), first?.start_ms(method))
+}
+
+/// Matches each segment of the run to a segment of the other run.
+fn match_segments(run: &Run, other: &Run) -> Vec<Option<usize>> {
+    if run.segments.len() == other.segments.len() {
+        return (0..run.segments.len()).map(Some).collect();
+    }
+    Aligner::new(&[run, other]).align().matches(1)
+}
@@ -0,0 +1,263 @@
+//! The comparisons module derives additional comparisons from the history of a Run, similar to the
+//! ones LiveSplit provides. A comparison consists of a split time for each segment of the run.
+
+use crate::{
+    history::{self, Attempt},
+    stats::Statistics,
+    Run, TimingMethod,
+};
+
+/// A comparison that can be derived from the history of a Run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    /// The average duration of each segment.
+    AverageSegments,
+    /// The median duration of each segment.
+    MedianSegments,
+    /// The fastest split time that was ever achieved at each split.
+    BestSplitTimes,
+    /// The split times of the most recent attempt.
+    LatestRun,
+    /// The slowest duration of each segment.
+    WorstSegments,
+    /// Segments that are all equally hard to achieve, while adding up to the personal best.
+    BalancedPb,
+}
+
+impl Comparison {
+    /// All the comparisons that can be derived.
+    pub const ALL: [Comparison; 6] = [
+        Comparison::AverageSegments,
+        Comparison::MedianSegments,
+        Comparison::BestSplitTimes,
+        Comparison::LatestRun,
+        Comparison::WorstSegments,
+        Comparison::BalancedPb,
+    ];
+
+    /// The name of the comparison as LiveSplit shows it.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Comparison::AverageSegments => "Average Segments",
+            Comparison::MedianSegments => "Median Segments",
+            Comparison::BestSplitTimes => "Best Split Times",
+            Comparison::LatestRun => "Latest Run",
+            Comparison::WorstSegments => "Worst Segments",
+            Comparison::BalancedPb => "Balanced PB",
+        }
+    }
+}
+
+/// The split times of all the comparisons for a single timing method in milliseconds.
+#[derive(Debug, Clone, Default)]
+pub struct ComparisonTimes {
+    /// The split time for the Average Segments comparison.
+    pub average_segments_ms: Option<f64>,
+    /// The split time for the Median Segments comparison.
+    pub median_segments_ms: Option<f64>,
+    /// The split time for the Best Split Times comparison.
+    pub best_split_times_ms: Option<f64>,
+    /// The split time for the Latest Run comparison.
+    pub latest_run_ms: Option<f64>,
+    /// The split time for the Worst Segments comparison.
+    pub worst_segments_ms: Option<f64>,
+    /// The split time for the Balanced PB comparison.
+    pub balanced_pb_ms: Option<f64>,
+}
+
+impl ComparisonTimes {
+    /// Gets the split time for the given comparison.
+    pub const fn get(&self, comparison: Comparison) -> Option<f64> {
+        match comparison {
+            Comparison::AverageSegments => self.average_segments_ms,
+            Comparison::MedianSegments => self.median_segments_ms,
+            Comparison::BestSplitTimes => self.best_split_times_ms,
+            Comparison::LatestRun => self.latest_run_ms,
+            Comparison::WorstSegments => self.worst_segments_ms,
+            Comparison::BalancedPb => self.balanced_pb_ms,
+        }
+    }
+
+    const fn get_mut(&mut self, comparison: Comparison) -> &mut Option<f64> {
+        match comparison {
+            Comparison::AverageSegments => &mut self.average_segments_ms,
+            Comparison::MedianSegments => &mut self.median_segments_ms,
+            Comparison::BestSplitTimes => &mut self.best_split_times_ms,
+            Comparison::LatestRun => &mut self.latest_run_ms,
+            Comparison::WorstSegments => &mut self.worst_segments_ms,
+            Comparison::BalancedPb => &mut self.balanced_pb_ms,
+        }
+    }
+}
+
+/// The split times of all the comparisons for a single segment.
+#[derive(Debug, Clone)]
+pub struct SegmentComparisons {
+    /// The index of the segment within the run. (This value starts at 0.)
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// The split times in real time.
+    pub realtime: ComparisonTimes,
+    /// The split times in game time.
+    pub gametime: ComparisonTimes,
+}
+
+impl SegmentComparisons {
+    /// Gets the split times for the given timing method.
+    pub const fn times(&self, method: TimingMethod) -> &ComparisonTimes {
+        match method {
+            TimingMethod::RealTime => &self.realtime,
+            TimingMethod::GameTime => &self.gametime,
+        }
+    }
+}
+
+impl Run {
+    /// Derives the split times of the given comparison for each segment of the run. This requires
+    /// the run to be retrieved with its history. A split time is `None` if there is not enough
+    /// history to derive it.
+    pub fn comparison(&self, comparison: Comparison, method: TimingMethod) -> Vec<Option<f64>> {
+        let attempts = history::attempts(self, method);
+        match comparison {
+            Comparison::AverageSegments => {
+                segment_stats_split_times(self, &attempts, |stats| stats.mean_ms())
+            }
+            Comparison::MedianSegments => {
+                segment_stats_split_times(self, &attempts, Statistics::median_ms)
+            }
+            Comparison::WorstSegments => {
+                segment_stats_split_times(self, &attempts, Statistics::max_ms)
+            }
+            Comparison::BestSplitTimes => (0..self.segments.len())
+                .map(|index| {
+                    attempts
+                        .iter()
+                        .filter_map(|attempt| attempt.split_time_ms(index))
+                        .min_by(f64::total_cmp)
+                })
+                .collect(),
+            Comparison::LatestRun => {
+                let latest = attempts
+                    .iter()
+                    .rev()
+                    .find(|attempt| attempt.segments.iter().any(Option::is_some));
+                (0..self.segments.len())
+                    .map(|index| latest?.split_time_ms(index))
+                    .collect()
+            }
+            Comparison::BalancedPb => balanced_pb(self, &attempts, method),
+        }
+    }
+
+    /// Derives all the comparisons for both timing methods for each segment of the run. This
+    /// requires the run to be retrieved with its history.
+    pub fn comparisons(&self) -> Vec<SegmentComparisons> {
+        let mut segments = self
+            .segments
+            .iter()
+            .map(|segment| SegmentComparisons {
+                segment_number: segment.segment_number,
+                name: segment.name.clone(),
+                realtime: ComparisonTimes::default(),
+                gametime: ComparisonTimes::default(),
+            })
+            .collect::<Vec<_>>();
+
+        for method in TimingMethod::ALL {
+            for comparison in Comparison::ALL {
+                for (segment, split_time) in
+                    segments.iter_mut().zip(self.comparison(comparison, method))
+                {
+                    let times = match method {
+                        TimingMethod::RealTime => &mut segment.realtime,
+                        TimingMethod::GameTime => &mut segment.gametime,
+                    };
+                    *times.get_mut(comparison) = split_time;
+                }
+            }
+        }
+
+        segments
+    }
+}
+
+fn segment_stats(run: &Run, attempts: &[Attempt]) -> Vec<Option<Statistics>> {
+    (0..run.segments.len())
+        .map(|index| {
+            Statistics::from_durations(
+                attempts
+                    .iter()
+                    .filter_map(|attempt| attempt.segment_duration_ms(index)),
+            )
+        })
+        .collect()
+}
+
+fn segment_stats_split_times(
+    run: &Run,
+    attempts: &[Attempt],
+    segment_time: impl Fn(&Statistics) -> f64,
+) -> Vec<Option<f64>> {
+    accumulate(
+        segment_stats(run, attempts)
+            .iter()
+            .map(|stats| stats.as_ref().map(&segment_time)),
+    )
+}
+
+/// Turns segment durations into split times. Once a segment is missing, none of the following
+/// split times can be determined anymore.
+fn accumulate(segment_times: impl Iterator<Item = Option<f64>>) -> Vec<Option<f64>> {
+    let mut split_time = Some(0.0);
+    segment_times
+        .map(|segment_time| {
+            split_time = Some(split_time? + segment_time?);
+            split_time
+        })
+        .collect()
+}
+
+/// Finds the percentile at which the segments add up to the personal best, so each segment is
+/// equally hard to achieve.
+fn balanced_pb(run: &Run, attempts: &[Attempt], method: TimingMethod) -> Vec<Option<f64>> {
+    let (Some(stats), Some(pb_ms)) = (
+        segment_stats(run, attempts)
+            .into_iter()
+            .collect::<Option<Vec<_>>>(),
+        run.duration_ms(method),
+    ) else {
+        return vec![None; run.segments.len()];
+    };
+
+    let total_at = |percentile| {
+        stats
+            .iter()
+            .map(|stats| stats.percentile_ms(percentile))
+            .sum::<f64>()
+    };
+
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..50 {
+        let mid = 0.5 * (low + high);
+        if total_at(mid) < pb_ms {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let percentile = 0.5 * (low + high);
+    let total_ms = total_at(percentile);
+    let scale = if total_ms > 0.0 {
+        pb_ms / total_ms
+    } else {
+        1.0
+    };
+
+    accumulate(
+        stats
+            .iter()
+            .map(|stats| Some(scale * stats.percentile_ms(percentile))),
+    )
+}
@@ -0,0 +1,37 @@
+use std::{
+    fmt::{self, Write as _},
+    io::{self, Write},
+};
+
+/// Writes a single CSV record, quoting the fields where necessary.
+pub(crate) fn write_record<W: Write>(
+    writer: &mut W,
+    fields: &[&dyn fmt::Display],
+) -> io::Result<()> {
+    let mut buf = String::new();
+    for (index, field) in fields.iter().enumerate() {
+        if index != 0 {
+            writer.write_all(b",")?;
+        }
+        buf.clear();
+        let _ = write!(buf, "{field}");
+        if buf.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", buf.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(buf.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\r\n")
+}
+
+/// Formats an optional value as an empty field if it is missing.
+pub(crate) struct OptionField<T>(pub(crate) Option<T>);
+
+impl<T: fmt::Display> fmt::Display for OptionField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => fmt::Display::fmt(value, f),
+            None => Ok(()),
+        }
+    }
+}
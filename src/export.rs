@@ -0,0 +1,231 @@
+//! The export module renders Runs in formats that are easy to use elsewhere, such as CSV for
+//! spreadsheets, JSON Lines for data pipelines and Markdown for posting a summary of a run.
+
+use crate::{
+    csv::{self, OptionField},
+    history,
+    timing::FormatTime,
+    Run, TimingMethod,
+};
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// Writes a CSV with a record for each segment of the run. It contains the segment's name, split
+/// time, duration, best segment and whether it was a gold, skipped or reduced, for both timing
+/// methods. All the times are in milliseconds. The split time and duration of skipped segments
+/// are empty.
+pub fn write_segments_csv<W: Write>(run: &Run, mut writer: W) -> io::Result<()> {
+    let mut header = vec![String::from("segment_number"), String::from("name")];
+    for prefix in ["realtime", "gametime"] {
+        for column in [
+            "split_ms",
+            "segment_ms",
+            "best_segment_ms",
+            "gold",
+            "skipped",
+            "reduced",
+        ] {
+            header.push(format!("{prefix}_{column}"));
+        }
+    }
+    let header = header
+        .iter()
+        .map(|c| c as &dyn fmt::Display)
+        .collect::<Vec<_>>();
+    csv::write_record(&mut writer, &header)?;
+
+    for segment in &run.segments {
+        let [real, game] = TimingMethod::ALL.map(|method| {
+            let skipped = segment.is_skipped(method);
+            (
+                OptionField(segment.end_ms(method).filter(|_| !skipped)),
+                OptionField(segment.duration_ms(method).filter(|_| !skipped)),
+                OptionField(segment.shortest_duration_ms(method)),
+                segment.is_gold(method),
+                skipped,
+                segment.is_reduced(method),
+            )
+        });
+        csv::write_record(
+            &mut writer,
+            &[
+                &segment.segment_number,
+                &segment.name,
+                &real.0,
+                &real.1,
+                &real.2,
+                &real.3,
+                &real.4,
+                &real.5,
+                &game.0,
+                &game.1,
+                &game.2,
+                &game.3,
+                &game.4,
+                &game.5,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a CSV with a record for each past attempt of the run. It contains the attempt's final
+/// time, followed by the duration of each segment in the attempt, for both timing methods. All
+/// the times are in milliseconds and are empty if the attempt didn't record them. This requires
+/// the run to be retrieved with its history.
+pub fn write_history_csv<W: Write>(run: &Run, mut writer: W) -> io::Result<()> {
+    let mut header = vec![
+        String::from("attempt_number"),
+        String::from("realtime_duration_ms"),
+        String::from("gametime_duration_ms"),
+    ];
+    for prefix in ["realtime", "gametime"] {
+        header.extend(run.segments.iter().map(|s| format!("{prefix}_{}", s.name)));
+    }
+    let header = header
+        .iter()
+        .map(|c| c as &dyn fmt::Display)
+        .collect::<Vec<_>>();
+    csv::write_record(&mut writer, &header)?;
+
+    let real = history::attempts(run, TimingMethod::RealTime);
+    let game = history::attempts(run, TimingMethod::GameTime);
+
+    for (real, game) in real.iter().zip(&game) {
+        let durations = [OptionField(real.duration_ms), OptionField(game.duration_ms)];
+        let segments = real
+            .segments
+            .iter()
+            .chain(&game.segments)
+            .map(|&duration| OptionField(duration))
+            .collect::<Vec<_>>();
+
+        let mut record: Vec<&dyn fmt::Display> = vec![&real.number, &durations[0], &durations[1]];
+        record.extend(segments.iter().map(|s| s as &dyn fmt::Display));
+        csv::write_record(&mut writer, &record)?;
+    }
+    Ok(())
+}
+
+/// Writes each run as a single line of JSON.
+pub fn write_json_lines<'a, W, I>(runs: I, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Run>,
+{
+    for run in runs {
+        serde_json::to_writer(&mut writer, run)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Writes a human-readable summary of the run as Markdown. It contains the game, category,
+/// runners, final time, sum of best and possible time save, followed by a table of the segments.
+/// The run's default timing method is used.
+pub fn write_markdown<W: Write>(run: &Run, mut writer: W) -> io::Result<()> {
+    let method = run
+        .default_timing_method()
+        .unwrap_or(TimingMethod::RealTime);
+
+    let game = run.game.as_ref().map_or("Unknown Game", |game| &game.name);
+    match &run.category {
+        Some(category) => writeln!(
+            writer,
+            "# {} \u{2014} {}",
+            MarkdownText(game),
+            MarkdownText(&category.name)
+        )?,
+        None => writeln!(writer, "# {}", MarkdownText(game))?,
+    }
+    writeln!(writer)?;
+
+    if !run.runners.is_empty() {
+        write!(writer, "- **Runners:** ")?;
+        for (index, runner) in run.runners.iter().enumerate() {
+            if index != 0 {
+                write!(writer, ", ")?;
+            }
+            let name = runner.display_name.as_deref().unwrap_or(&runner.name);
+            write!(writer, "{}", MarkdownText(name))?;
+        }
+        writeln!(writer)?;
+    }
+
+    let method_name = match method {
+        TimingMethod::RealTime => "Real Time",
+        TimingMethod::GameTime => "Game Time",
+    };
+    writeln!(writer, "- **Timing Method:** {method_name}")?;
+    if let Some(duration) = run.duration_ms(method) {
+        writeln!(writer, "- **Time:** {}", FormatTime(duration))?;
+    }
+    if let Some(sum_of_best) = run.sum_of_best_ms(method) {
+        writeln!(writer, "- **Sum of Best:** {}", FormatTime(sum_of_best))?;
+    }
+    let time_save = run.possible_time_save(method);
+    writeln!(
+        writer,
+        "- **Possible Time Save:** {}",
+        FormatTime(time_save.total_possible_time_save_ms),
+    )?;
+    if let Some(attempts) = run.attempts {
+        writeln!(writer, "- **Attempts:** {attempts}")?;
+    }
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "| # | Segment | Split Time | Segment Time | Best Segment | Possible Time Save |"
+    )?;
+    writeln!(writer, "|--:|---|--:|--:|--:|--:|")?;
+    for (segment, time_save) in run.segments.iter().zip(&time_save.segments) {
+        let time = |time: Option<f64>| {
+            time.map_or_else(|| String::from("\u{2014}"), |t| FormatTime(t).to_string())
+        };
+        let (split, duration) = if segment.is_skipped(method) {
+            (time(None), time(None))
+        } else {
+            (
+                time(segment.end_ms(method)),
+                time(segment.duration_ms(method)),
+            )
+        };
+        let duration = if segment.is_gold(method) {
+            format!("**{duration}**")
+        } else {
+            duration
+        };
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} | {} | {} |",
+            segment.segment_number + 1,
+            MarkdownText(&segment.name),
+            split,
+            duration,
+            time(segment.shortest_duration_ms(method)),
+            time(time_save.possible_time_save_ms),
+        )?;
+    }
+    Ok(())
+}
+
+/// Escapes the characters that have a special meaning in Markdown.
+struct MarkdownText<'a>(&'a str);
+
+impl fmt::Display for MarkdownText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            if matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+            ) {
+                write!(f, "\\")?;
+            }
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! The funnel module analyzes where the attempts of a Run end. For every segment it determines how
+//! many attempts reached it and how many of them were reset before completing it.
+
+use crate::{csv, history, Run, TimingMethod};
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// The attempts of a Run broken down by how far they got.
+#[derive(Debug, Clone)]
+pub struct Funnel {
+    /// The total number of attempts the funnel is based on.
+    pub attempts: u32,
+    /// The stages of the funnel, one for each segment of the run.
+    pub stages: Vec<FunnelStage>,
+}
+
+/// How many attempts reached and completed a single segment.
+#[derive(Debug, Clone)]
+pub struct FunnelStage {
+    /// The index of the segment within the run. (This value starts at 0.)
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// The number of attempts that reached the segment.
+    pub reached: u32,
+    /// The number of attempts that completed the segment.
+    pub completed: u32,
+}
+
+impl FunnelStage {
+    /// The number of attempts that were reset during the segment.
+    pub const fn resets(&self) -> u32 {
+        self.reached - self.completed
+    }
+
+    /// The fraction of the attempts reaching the segment that were reset during it. This is a
+    /// value between 0 and 1. If no attempt reached the segment, the reset rate is 0.
+    pub fn reset_rate(&self) -> f64 {
+        if self.reached == 0 {
+            0.0
+        } else {
+            self.resets() as f64 / self.reached as f64
+        }
+    }
+}
+
+impl Funnel {
+    /// The fraction of all attempts that made it to the end of the run.
+    pub fn finish_rate(&self) -> f64 {
+        match self.stages.last() {
+            Some(stage) if self.attempts != 0 => stage.completed as f64 / self.attempts as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// Writes the funnel as CSV, with a header and a record for each segment.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        csv::write_record(
+            &mut writer,
+            &[
+                &"segment_number",
+                &"name",
+                &"reached",
+                &"completed",
+                &"resets",
+                &"reset_rate",
+            ],
+        )?;
+        for stage in &self.stages {
+            csv::write_record(
+                &mut writer,
+                &[
+                    &stage.segment_number,
+                    &stage.name,
+                    &stage.reached,
+                    &stage.completed,
+                    &stage.resets(),
+                    &stage.reset_rate(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the funnel as a plain text table.
+impl fmt::Display for Funnel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .stages
+            .iter()
+            .map(|stage| stage.name.chars().count())
+            .chain([7])
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "{:name_width$}  {:>7}  {:>9}  {:>6}  {:>10}",
+            "Segment", "Reached", "Completed", "Resets", "Reset Rate",
+        )?;
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:name_width$}  {:>7}  {:>9}  {:>6}  {:>9.2}%",
+                stage.name,
+                stage.reached,
+                stage.completed,
+                stage.resets(),
+                100.0 * stage.reset_rate(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Run {
+    /// Builds the funnel of the run's attempts for the given timing method. This requires the run
+    /// to be retrieved with its history.
+    pub fn funnel(&self, method: TimingMethod) -> Funnel {
+        let attempts = history::attempts(self, method);
+
+        let stages = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let (mut reached, mut completed) = (0, 0);
+                for attempt in &attempts {
+                    let furthest = attempt.furthest_segment();
+                    if index == 0 || furthest >= Some(index - 1) {
+                        reached += 1;
+                    }
+                    if furthest >= Some(index) {
+                        completed += 1;
+                    }
+                }
+                FunnelStage {
+                    segment_number: segment.segment_number,
+                    name: segment.name.clone(),
+                    reached,
+                    completed,
+                }
+            })
+            .collect();
+
+        Funnel {
+            attempts: attempts.len() as u32,
+            stages,
+        }
+    }
+}
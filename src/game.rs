@@ -5,9 +5,11 @@
 
 use crate::{
     get_json,
+    page::{self, Page, PageSettings},
     wrapper::{ContainsCategories, ContainsGame, ContainsGames, ContainsRunners, ContainsRuns},
     Category, Client, Error, Game, Run, Runner,
 };
+use futures_util::{Stream, TryStreamExt};
 use reqwest::Url;
 
 impl Game {
@@ -21,53 +23,131 @@ impl Game {
         self::get(client, shortname).await
     }
 
-    /// Gets the Categories that belong to the Game based on the shortened title of the game.
+    /// Gets a Game based on the ID of the game.
+    pub async fn get_by_id(client: &Client, id: &str) -> Result<Game, Error> {
+        self::get_by_id(client, id).await
+    }
+
+    /// Finds the Game with exactly the given name, ignoring case.
+    pub async fn find_by_name(client: &Client, name: &str) -> Result<Option<Game>, Error> {
+        self::find_by_name(client, name).await
+    }
+
+    /// Gets the Categories that belong to the Game based on the shortened title of the game, or
+    /// its ID if it has no shortened title.
     pub async fn categories(&self, client: &Client) -> Result<Vec<Category>, Error> {
-        get_categories(
-            client,
-            self.shortname
-                .as_deref()
-                .ok_or(Error::UnidentifiableResource)?,
-        )
-        .await
+        get_categories(client, self.resolve_identifier(client).await?).await
     }
 
-    /// Gets the Runs that belong to the Game based on the shortened title of the game.
+    /// Gets the Runs that belong to the Game based on the shortened title of the game, or its ID
+    /// if it has no shortened title.
     pub async fn runs(&self, client: &Client) -> Result<Vec<Run>, Error> {
-        get_runs(
-            client,
-            self.shortname
-                .as_deref()
-                .ok_or(Error::UnidentifiableResource)?,
-        )
-        .await
+        get_runs(client, self.resolve_identifier(client).await?).await
     }
 
-    /// Gets the Runners that belong to the Game based on the shortened title of the game.
+    /// Gets the Runs that belong to the Game based on the shortened title of the game, or its ID
+    /// if it has no shortened title. All the pages of the Runs are retrieved, one after another.
+    pub async fn runs_all(&self, client: &Client) -> Result<Vec<Run>, Error> {
+        get_runs_all(client, self.resolve_identifier(client).await?).await
+    }
+
+    /// Streams the Runs that belong to the Game based on the shortened title of the game, or its
+    /// ID if it has no shortened title. The Runs are requested one page at a time as they are
+    /// needed.
+    pub async fn runs_stream<'a>(
+        &self,
+        client: &'a Client,
+        settings: PageSettings,
+    ) -> Result<impl Stream<Item = Result<Run, Error>> + 'a, Error> {
+        let identifier = self.resolve_identifier(client).await?;
+        Ok(get_runs_stream(client, identifier, settings))
+    }
+
+    /// Gets the Runners that belong to the Game based on the shortened title of the game, or its
+    /// ID if it has no shortened title.
     pub async fn runners(&self, client: &Client) -> Result<Vec<Runner>, Error> {
-        get_runners(
-            client,
-            self.shortname
-                .as_deref()
-                .ok_or(Error::UnidentifiableResource)?,
-        )
-        .await
+        get_runners(client, self.resolve_identifier(client).await?).await
+    }
+
+    /// The shortened title of the game, or its ID if it has no shortened title. The API accepts
+    /// either of them to identify the game.
+    pub(crate) fn identifier(&self) -> Result<&str, Error> {
+        self.shortname
+            .as_deref()
+            .filter(|shortname| !shortname.is_empty())
+            .or_else(|| Some(&*self.id).filter(|id| !id.is_empty()))
+            .ok_or(Error::UnidentifiableResource)
+    }
+
+    /// The shortened title of the game, or its ID if it has no shortened title. Another game may
+    /// have the ID as its shortened title, so the ID is only used once it is confirmed to refer to
+    /// this game.
+    async fn resolve_identifier(&self, client: &Client) -> Result<&str, Error> {
+        let identifier = self.identifier()?;
+        if identifier == &*self.id {
+            get_by_id(client, identifier).await?;
+        }
+        Ok(identifier)
     }
 }
 
 /// Searches for a Game based on the name of the game.
 pub async fn search(client: &Client, name: &str) -> Result<Vec<Game>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/games").unwrap();
-    url.query_pairs_mut().append_pair("search", name);
-
-    let ContainsGames { games } = get_json(client, client.client.get(url)).await?;
+    let ContainsGames { games } =
+        get_json(client, client.client.get(search_url(client, name))).await?;
 
     Ok(games)
 }
 
+/// Searches for a Game based on the name of the game. All the pages of the results are retrieved,
+/// one after another.
+pub async fn search_all(client: &Client, name: &str) -> Result<Vec<Game>, Error> {
+    search_stream(client, name, PageSettings::default())
+        .try_collect()
+        .await
+}
+
+/// Gets a single page of the Games that match the name of the game.
+pub async fn search_page(
+    client: &Client,
+    name: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Game>, Error> {
+    page::get_page(
+        client,
+        search_url(client, name),
+        page,
+        per_page,
+        |g: ContainsGames| g.games,
+    )
+    .await
+}
+
+/// Streams the Games that match the name of the game. The Games are requested one page at a
+/// time as they are needed.
+pub fn search_stream<'a>(
+    client: &'a Client,
+    name: &str,
+    settings: PageSettings,
+) -> impl Stream<Item = Result<Game, Error>> + 'a {
+    page::stream(
+        client,
+        search_url(client, name),
+        settings,
+        |g: ContainsGames| g.games,
+    )
+}
+
+fn search_url(client: &Client, name: &str) -> Url {
+    let mut url = client.api_url("games");
+    url.query_pairs_mut().append_pair("search", name);
+    url
+}
+
 /// Gets a Game based on the shortened title of the game.
 pub async fn get(client: &Client, shortname: &str) -> Result<Game, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/games").unwrap();
+    let mut url = client.api_url("games");
     url.path_segments_mut().unwrap().push(shortname);
 
     let ContainsGame { game } = get_json(client, client.client.get(url)).await?;
@@ -75,9 +155,40 @@ pub async fn get(client: &Client, shortname: &str) -> Result<Game, Error> {
     Ok(game)
 }
 
-/// Gets the Categories that belong to a Game based on the shortened title of the game.
+/// Gets a Game based on the ID of the game. The API looks up games by their shortened title or
+/// their ID on the same endpoint, so if a game with that shortened title is found instead, the ID
+/// can't identify the game and an [`Error::UnidentifiableResource`] is returned rather than the
+/// wrong game.
+pub async fn get_by_id(client: &Client, id: &str) -> Result<Game, Error> {
+    let game = get(client, id).await?;
+    if &*game.id != id {
+        return Err(Error::UnidentifiableResource);
+    }
+    Ok(game)
+}
+
+/// Finds the Game with exactly the given name, ignoring case. This searches for the name and picks
+/// the matching Game out of the results. The pages of the results are requested until a Game with
+/// the name in the same case is found.
+pub async fn find_by_name(client: &Client, name: &str) -> Result<Option<Game>, Error> {
+    let name = name.trim();
+    let lowercase = name.to_lowercase();
+    let mut games = std::pin::pin!(search_stream(client, name, PageSettings::default()));
+    let mut ignoring_case = None;
+    while let Some(game) = games.try_next().await? {
+        if &*game.name == name {
+            return Ok(Some(game));
+        }
+        if ignoring_case.is_none() && game.name.trim().to_lowercase() == lowercase {
+            ignoring_case = Some(game);
+        }
+    }
+    Ok(ignoring_case)
+}
+
+/// Gets the Categories that belong to a Game based on the shortened title or the ID of the game.
 pub async fn get_categories(client: &Client, shortname: &str) -> Result<Vec<Category>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/games").unwrap();
+    let mut url = client.api_url("games");
     url.path_segments_mut()
         .unwrap()
         .extend(&[shortname, "categories"]);
@@ -87,21 +198,66 @@ pub async fn get_categories(client: &Client, shortname: &str) -> Result<Vec<Cate
     Ok(categories)
 }
 
-/// Gets the Runs that belong to a Game based on the shortened title of the game.
+/// Gets the Runs that belong to a Game based on the shortened title or the ID of the game.
 pub async fn get_runs(client: &Client, shortname: &str) -> Result<Vec<Run>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/games").unwrap();
+    let ContainsRuns { runs } =
+        get_json(client, client.client.get(runs_url(client, shortname))).await?;
+
+    Ok(runs)
+}
+
+/// Gets the Runs that belong to a Game based on the shortened title or the ID of the game. All the
+/// pages of the Runs are retrieved, one after another.
+pub async fn get_runs_all(client: &Client, shortname: &str) -> Result<Vec<Run>, Error> {
+    get_runs_stream(client, shortname, PageSettings::default())
+        .try_collect()
+        .await
+}
+
+/// Gets a single page of the Runs that belong to a Game based on the shortened title or the ID of
+/// the game.
+pub async fn get_runs_page(
+    client: &Client,
+    shortname: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Run>, Error> {
+    page::get_page(
+        client,
+        runs_url(client, shortname),
+        page,
+        per_page,
+        |r: ContainsRuns| r.runs,
+    )
+    .await
+}
+
+/// Streams the Runs that belong to a Game based on the shortened title or the ID of the game. The
+/// Runs are requested one page at a time as they are needed.
+pub fn get_runs_stream<'a>(
+    client: &'a Client,
+    shortname: &str,
+    settings: PageSettings,
+) -> impl Stream<Item = Result<Run, Error>> + 'a {
+    page::stream(
+        client,
+        runs_url(client, shortname),
+        settings,
+        |r: ContainsRuns| r.runs,
+    )
+}
+
+fn runs_url(client: &Client, shortname: &str) -> Url {
+    let mut url = client.api_url("games");
     url.path_segments_mut()
         .unwrap()
         .extend(&[shortname, "runs"]);
-
-    let ContainsRuns { runs } = get_json(client, client.client.get(url)).await?;
-
-    Ok(runs)
+    url
 }
 
-/// Gets the Runners that belong to a Game based on the shortened title of the game.
+/// Gets the Runners that belong to a Game based on the shortened title or the ID of the game.
 pub async fn get_runners(client: &Client, shortname: &str) -> Result<Vec<Runner>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/games").unwrap();
+    let mut url = client.api_url("games");
     url.path_segments_mut()
         .unwrap()
         .extend(&[shortname, "runners"]);
@@ -0,0 +1,77 @@
+use crate::{Run, TimingMethod};
+use std::collections::BTreeMap;
+
+/// A single attempt of a run, reconstructed from the histories of the run and its segments.
+pub(crate) struct Attempt {
+    pub(crate) number: u32,
+    /// The duration of the whole attempt, if it was finished.
+    pub(crate) duration_ms: Option<f64>,
+    /// The duration of each segment, if it was recorded. Skipped segments and segments after a
+    /// reset don't have a duration.
+    pub(crate) segments: Vec<Option<f64>>,
+}
+
+impl Attempt {
+    /// Whether the attempt made it all the way to the end of the run.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.duration_ms.is_some() || matches!(self.segments.last(), Some(Some(_)))
+    }
+
+    /// The index of the last segment that was completed by the attempt. Segments that were
+    /// skipped before it are considered completed too.
+    pub(crate) fn furthest_segment(&self) -> Option<usize> {
+        if self.is_finished() {
+            return self.segments.len().checked_sub(1);
+        }
+        self.segments.iter().rposition(Option::is_some)
+    }
+
+    /// The duration of the segment, if it only covers the segment itself. If the previous segment
+    /// was skipped, the recorded duration also includes the skipped segment, so it is ignored.
+    pub(crate) fn segment_duration_ms(&self, segment: usize) -> Option<f64> {
+        if segment > 0 {
+            self.segments[segment - 1]?;
+        }
+        self.segments[segment]
+    }
+
+    /// The split time at the end of the segment, if the segment has a recorded duration. The
+    /// duration of a segment after a skipped segment includes the skipped one, so the split time
+    /// is the sum of all the recorded durations up to the segment.
+    pub(crate) fn split_time_ms(&self, segment: usize) -> Option<f64> {
+        self.segments[segment]?;
+        Some(self.segments[..=segment].iter().flatten().sum())
+    }
+}
+
+/// Reconstructs all the attempts of the run in order, for the given timing method.
+pub(crate) fn attempts(run: &Run, method: TimingMethod) -> Vec<Attempt> {
+    let segment_count = run.segments.len();
+    let mut attempts = BTreeMap::new();
+
+    for history in run.histories.iter().flatten() {
+        attempt(&mut attempts, history.attempt_number, segment_count).duration_ms =
+            history.duration_ms(method);
+    }
+
+    for (index, segment) in run.segments.iter().enumerate() {
+        for history in segment.histories.iter().flatten() {
+            attempt(&mut attempts, history.attempt_number, segment_count).segments[index] =
+                history.duration_ms(method);
+        }
+    }
+
+    attempts.into_values().collect()
+}
+
+fn attempt(
+    attempts: &mut BTreeMap<u32, Attempt>,
+    number: u32,
+    segment_count: usize,
+) -> &mut Attempt {
+    attempts.entry(number).or_insert_with(|| Attempt {
+        number,
+        duration_ms: None,
+        segments: vec![None; segment_count],
+    })
+}
@@ -0,0 +1,216 @@
+//! The hydrate module upgrades resources that are embedded in other resources to their full form.
+//! For example the Game of a Run doesn't include its Categories, and the Run of a race Entry
+//! doesn't include its segments. If the Client has a cache capacity set, hydrated resources are
+//! cached, so hydrating the same resource again doesn't make another request.
+//!
+//! ```no_run
+//! # use splits_io_api::{hydrate::Hydrate, Client, Run};
+//! # async fn hydrate(client: &Client, run: &Run) -> Result<(), splits_io_api::Error> {
+//! if let Some(game) = &run.game {
+//!     let game = game.hydrate(client).await?;
+//!     println!("{:?}", game.categories);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{category, game, run, runner, Category, Client, Error, Game, Run, Runner};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{Mutex, PoisonError},
+};
+
+/// A resource that may be embedded in another resource in an incomplete form.
+#[cfg(not(target_family = "wasm"))]
+pub trait Hydrate: Sized {
+    /// Retrieves the full form of the resource by the best identifier it has. Resources that are
+    /// already complete or are in the cache of the Client are returned without making a request.
+    fn hydrate(&self, client: &Client) -> impl Future<Output = Result<Self, Error>> + Send;
+}
+
+/// A resource that may be embedded in another resource in an incomplete form.
+#[cfg(target_family = "wasm")]
+pub trait Hydrate: Sized {
+    /// Retrieves the full form of the resource by the best identifier it has. Resources that are
+    /// already complete or are in the cache of the Client are returned without making a request.
+    // The requests of the browser's fetch API can't be sent to other threads.
+    fn hydrate(&self, client: &Client) -> impl Future<Output = Result<Self, Error>>;
+}
+
+/// The resources that were hydrated before, by their identifiers. The cache is disabled unless
+/// a capacity is set.
+#[derive(Default)]
+pub(crate) struct Cache {
+    capacity: usize,
+    games: Resources<Game>,
+    categories: Resources<Category>,
+    runs: Resources<Run>,
+    runners: Resources<Runner>,
+}
+
+/// The cached resources of a single kind. The resource that was cached first is evicted once the
+/// capacity is exceeded.
+struct Resources<T> {
+    resources: HashMap<Box<str>, T>,
+    order: VecDeque<Box<str>>,
+}
+
+impl<T> Default for Resources<T> {
+    fn default() -> Self {
+        Self {
+            resources: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> Resources<T> {
+    fn insert(&mut self, key: &str, resource: T, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        if self.resources.insert(key.into(), resource).is_none() {
+            self.order.push_back(key.into());
+        }
+        while self.resources.len() > capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.resources.remove(&oldest);
+        }
+    }
+}
+
+impl Client {
+    /// Sets how many Games, Categories, Runs and Runners each are kept in the cache of hydrated
+    /// resources. Once there are more, the ones that were cached first are removed. The cache is
+    /// disabled with a capacity of 0, which is the default. Cached resources aren't retrieved
+    /// again, so changes on splits.io aren't noticed until they are removed from the cache.
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        let cache = self.cache.get_mut().unwrap_or_else(PoisonError::into_inner);
+        *cache = Cache {
+            capacity,
+            ..Cache::default()
+        };
+    }
+
+    /// Clears the cache of hydrated resources, so they are retrieved again the next time they are
+    /// hydrated.
+    pub fn clear_cache(&self) {
+        let mut cache = lock(&self.cache);
+        *cache = Cache {
+            capacity: cache.capacity,
+            ..Cache::default()
+        };
+    }
+}
+
+impl Hydrate for Game {
+    async fn hydrate(&self, client: &Client) -> Result<Self, Error> {
+        if self.categories.is_some() {
+            return Ok(self.clone());
+        }
+        let identifier = self.identifier()?;
+        cached(
+            client,
+            identifier,
+            |cache| &mut cache.games,
+            || async {
+                if identifier == &*self.id {
+                    game::get_by_id(client, identifier).await
+                } else {
+                    game::get(client, identifier).await
+                }
+            },
+        )
+        .await
+    }
+}
+
+impl Hydrate for Category {
+    async fn hydrate(&self, client: &Client) -> Result<Self, Error> {
+        if self.id.is_empty() {
+            return Err(Error::UnidentifiableResource);
+        }
+        cached(
+            client,
+            &self.id,
+            |cache| &mut cache.categories,
+            || category::get(client, &self.id),
+        )
+        .await
+    }
+}
+
+impl Hydrate for Run {
+    /// Retrieves the Run with its segments and history.
+    async fn hydrate(&self, client: &Client) -> Result<Self, Error> {
+        if self.histories.is_some() && !self.segments.is_empty() {
+            return Ok(self.clone());
+        }
+        let id = self.id.as_deref().ok_or(Error::UnidentifiableResource)?;
+        cached(
+            client,
+            id,
+            |cache| &mut cache.runs,
+            || run::get(client, id, true),
+        )
+        .await
+    }
+}
+
+impl Hydrate for Runner {
+    async fn hydrate(&self, client: &Client) -> Result<Self, Error> {
+        if self.name.is_empty() {
+            return Err(Error::UnidentifiableResource);
+        }
+        let key = self.name.to_lowercase();
+        cached(
+            client,
+            &key,
+            |cache| &mut cache.runners,
+            || runner::get(client, &self.name),
+        )
+        .await
+    }
+}
+
+impl<T: Hydrate + Sync> Hydrate for Option<T> {
+    async fn hydrate(&self, client: &Client) -> Result<Self, Error> {
+        match self {
+            Some(resource) => resource.hydrate(client).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Looks up the resource in the cache, or retrieves and caches it if it isn't in there yet.
+async fn cached<T, F>(
+    client: &Client,
+    key: &str,
+    resources: fn(&mut Cache) -> &mut Resources<T>,
+    fetch: impl FnOnce() -> F,
+) -> Result<T, Error>
+where
+    T: Clone,
+    F: Future<Output = Result<T, Error>>,
+{
+    let cached = resources(&mut lock(&client.cache))
+        .resources
+        .get(key)
+        .cloned();
+    if let Some(resource) = cached {
+        return Ok(resource);
+    }
+    let resource = fetch().await?;
+    let mut cache = lock(&client.cache);
+    let capacity = cache.capacity;
+    resources(&mut cache).insert(key, resource.clone(), capacity);
+    Ok(resource)
+}
+
+fn lock(cache: &Mutex<Cache>) -> std::sync::MutexGuard<'_, Cache> {
+    // The cache is always left in a consistent state, even if a thread panicked.
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
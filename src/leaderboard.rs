@@ -0,0 +1,109 @@
+//! The leaderboard module ranks the Runs of a Category. Each runner is only ranked with their
+//! fastest run, which may be a run they performed together with other runners.
+
+use crate::{Run, Runner, TimingMethod};
+use std::collections::{btree_map::Entry, BTreeMap};
+
+/// A leaderboard of the runs of a category.
+#[derive(Debug)]
+pub struct Leaderboard {
+    /// The timing method the runs are ranked by.
+    pub method: TimingMethod,
+    /// The entries of the leaderboard, from fastest to slowest.
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// A single entry of a leaderboard.
+#[derive(Debug)]
+pub struct LeaderboardEntry {
+    /// The rank of the entry. Entries with the same time share the same rank, with the next rank
+    /// being skipped. (This value starts at 1.)
+    pub rank: u32,
+    /// The runner the entry is for.
+    pub runner: Runner,
+    /// The fastest run of the runner. This may be a run with multiple runners, in which case each
+    /// of them has their own entry for it, unless they have a faster run of their own.
+    pub run: Run,
+    /// The duration of the run in milliseconds.
+    pub duration_ms: f64,
+    /// How much slower the run is than the first place in milliseconds.
+    pub gap_ms: f64,
+}
+
+impl LeaderboardEntry {
+    /// All the runners who performed the run, including the runner the entry is for.
+    pub fn runners(&self) -> &[Runner] {
+        &self.run.runners
+    }
+}
+
+impl Leaderboard {
+    /// Ranks the runners by the duration of their fastest run for the given timing method. Runs
+    /// with multiple runners count for each of the runners. Runners are told apart by their ID, so
+    /// names that only differ in case are different runners. Runs that nobody has claimed and
+    /// runs without a duration for the timing method are left out, as they can't be attributed
+    /// to anyone or can't be ranked. If the same time was achieved multiple times, the run that
+    /// was uploaded first is kept.
+    pub fn from_runs(runs: impl IntoIterator<Item = Run>, method: TimingMethod) -> Self {
+        let mut fastest = BTreeMap::<Box<str>, (f64, Run, Runner)>::new();
+
+        for run in runs {
+            let Some(duration_ms) = run.duration_ms(method).filter(|&d| d > 0.0) else {
+                continue;
+            };
+            for runner in &run.runners {
+                match fastest.entry(runner.id.clone()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert((duration_ms, run.clone(), runner.clone()));
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let (best_ms, best, _) = entry.get();
+                        if duration_ms < *best_ms
+                            || (duration_ms == *best_ms && run.created_at < best.created_at)
+                        {
+                            entry.insert((duration_ms, run.clone(), runner.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut runs = fastest.into_values().collect::<Vec<_>>();
+        runs.sort_by(|(a_ms, a, a_runner), (b_ms, b, b_runner)| {
+            a_ms.total_cmp(b_ms)
+                .then_with(|| a.created_at.cmp(&b.created_at))
+                .then_with(|| a_runner.name.cmp(&b_runner.name))
+        });
+
+        let first_ms = runs.first().map_or(0.0, |&(duration_ms, ..)| duration_ms);
+        let mut entries = Vec::<LeaderboardEntry>::with_capacity(runs.len());
+        for (position, (duration_ms, run, runner)) in runs.into_iter().enumerate() {
+            let rank = match entries.last() {
+                Some(previous) if previous.duration_ms == duration_ms => previous.rank,
+                _ => position as u32 + 1,
+            };
+            entries.push(LeaderboardEntry {
+                rank,
+                runner,
+                run,
+                duration_ms,
+                gap_ms: duration_ms - first_ms,
+            });
+        }
+
+        Self { method, entries }
+    }
+
+    /// Finds the entry of the runner with the given name. A runner with exactly the given name is
+    /// preferred, otherwise names are compared without regard to case.
+    pub fn find_runner(&self, name: &str) -> Option<&LeaderboardEntry> {
+        self.entries
+            .iter()
+            .find(|entry| &*entry.runner.name == name)
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .find(|entry| entry.runner.name.eq_ignore_ascii_case(name))
+            })
+    }
+}
@@ -58,17 +58,45 @@
 
 use std::fmt;
 
-use reqwest::{header::AUTHORIZATION, RequestBuilder, Response, StatusCode};
+use reqwest::{header::AUTHORIZATION, RequestBuilder, Response, StatusCode, Url};
 
+pub mod align;
+pub mod bulk;
 pub mod category;
+pub mod category_names;
+pub mod chapters;
+pub mod compare;
+pub mod comparisons;
+mod csv;
 // pub mod event;
+pub mod export;
+pub mod funnel;
 pub mod game;
+mod history;
+pub mod hydrate;
+pub mod leaderboard;
+pub mod lss;
+pub mod pace;
+pub mod page;
+pub mod profile;
+pub mod progression;
+pub mod query;
 pub mod race;
+pub mod resource;
 pub mod run;
 pub mod runner;
 mod schema;
+pub mod search_index;
+pub mod simulation;
+pub mod stats;
+pub mod sum_of_best;
+pub mod time_save;
+mod timing;
+pub mod video;
+pub mod watch;
 mod wrapper;
 pub use schema::*;
+pub use timing::TimingMethod;
 
 pub use uuid;
 
@@ -76,27 +104,39 @@ pub use uuid;
 /// authentication to all API endpoints.
 pub struct Client {
     client: reqwest::Client,
+    base_url: Url,
     access_token: Option<String>,
+    cache: std::sync::Mutex<hydrate::Cache>,
 }
 
 impl Default for Client {
     fn default() -> Self {
-        #[allow(unused_mut)]
-        let mut builder = reqwest::Client::builder();
-        #[cfg(not(target_family = "wasm"))]
-        {
-            builder = builder.http2_prior_knowledge();
-            #[cfg(feature = "rustls")]
-            {
-                builder = builder.use_rustls_tls();
-            }
-        }
-
         Client {
-            client: builder.build().unwrap(),
+            client: http_client(true),
+            base_url: Url::parse("https://splits.io").unwrap(),
             access_token: None,
+            cache: Default::default(),
+        }
+    }
+}
+
+/// Builds the HTTP client. splits.io itself is known to speak HTTP/2, so it can be used right
+/// away. Other instances, such as a local server over plain HTTP, may only speak HTTP/1.1.
+#[allow(unused_variables)]
+fn http_client(http2_prior_knowledge: bool) -> reqwest::Client {
+    #[allow(unused_mut)]
+    let mut builder = reqwest::Client::builder();
+    #[cfg(not(target_family = "wasm"))]
+    {
+        if http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        #[cfg(feature = "rustls")]
+        {
+            builder = builder.use_rustls_tls();
         }
     }
+    builder.build().unwrap()
 }
 
 impl Client {
@@ -105,6 +145,27 @@ impl Client {
         Self::default()
     }
 
+    /// Creates a new client for an instance of splits.io hosted at the given base URL, such as
+    /// `http://localhost:3000`. Returns `None` if the URL can't be a base, such as a `mailto:` URL.
+    pub fn with_base_url(base_url: Url) -> Option<Self> {
+        if base_url.cannot_be_a_base() {
+            return None;
+        }
+        let http2_prior_knowledge =
+            base_url.scheme() == "https" && base_url.host_str() == Some("splits.io");
+        Some(Self {
+            client: http_client(http2_prior_knowledge),
+            base_url,
+            access_token: None,
+            cache: Default::default(),
+        })
+    }
+
+    /// The base URL of the instance of splits.io the client accesses.
+    pub const fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
     /// Sets the client's access token, which can be used to authenticate to all API endpoints.
     pub fn set_access_token(&mut self, access_token: &str) {
         let buf = self.access_token.get_or_insert_with(String::new);
@@ -112,6 +173,17 @@ impl Client {
         buf.push_str("Bearer ");
         buf.push_str(access_token);
     }
+
+    /// The URL of the API endpoint for the kind of resource, such as `runs`.
+    fn api_url(&self, resource: &str) -> Url {
+        let mut url = self.base_url.clone();
+        // The base URL is checked to be able to be a base when the client is created.
+        url.path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .extend(&["api", "v4", resource]);
+        url
+    }
 }
 
 #[derive(Debug)]
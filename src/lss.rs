@@ -0,0 +1,192 @@
+//! The lss module converts Runs into LiveSplit splits files. This allows loading a run into
+//! LiveSplit, even if the original splits file wasn't recorded with LiveSplit. For the history of
+//! the run to be included, the run needs to be retrieved with its history.
+
+use crate::{history, Run, TimingMethod};
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// Writes the run as a LiveSplit splits file (`.lss`). This includes the game and category name,
+/// the segments with their personal best split times and best segments, the history of each
+/// segment and the attempt history.
+pub fn write<W: Write>(run: &Run, mut writer: W) -> io::Result<()> {
+    let real = history::attempts(run, TimingMethod::RealTime);
+    let game = history::attempts(run, TimingMethod::GameTime);
+    let attempt_count = run.attempts.unwrap_or(real.len() as u32);
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<Run version="1.7.0">"#)?;
+    writeln!(writer, "  <GameIcon />")?;
+    writeln!(
+        writer,
+        "  <GameName>{}</GameName>",
+        Escape(run.game.as_ref().map_or("", |game| &game.name)),
+    )?;
+    writeln!(
+        writer,
+        "  <CategoryName>{}</CategoryName>",
+        Escape(run.category.as_ref().map_or("", |category| &category.name)),
+    )?;
+    writeln!(writer, "  <LayoutPath></LayoutPath>")?;
+    writeln!(writer, "  <Metadata>")?;
+    writeln!(
+        writer,
+        r#"    <Run id="{}" />"#,
+        Escape(run.srdc_id.as_deref().unwrap_or_default()),
+    )?;
+    writeln!(writer, r#"    <Platform usesEmulator="False"></Platform>"#)?;
+    writeln!(writer, "    <Region></Region>")?;
+    writeln!(writer, "    <Variables />")?;
+    writeln!(writer, "  </Metadata>")?;
+    writeln!(writer, "  <Offset>00:00:00</Offset>")?;
+    writeln!(writer, "  <AttemptCount>{attempt_count}</AttemptCount>")?;
+
+    writeln!(writer, "  <AttemptHistory>")?;
+    for (real, game) in real.iter().zip(&game) {
+        write_times(
+            &mut writer,
+            "    ",
+            "Attempt",
+            &format!(r#"id="{}""#, real.number),
+            real.duration_ms,
+            game.duration_ms,
+        )?;
+    }
+    writeln!(writer, "  </AttemptHistory>")?;
+
+    writeln!(writer, "  <Segments>")?;
+    for (index, segment) in run.segments.iter().enumerate() {
+        writeln!(writer, "    <Segment>")?;
+        writeln!(writer, "      <Name>{}</Name>", Escape(&segment.name))?;
+        writeln!(writer, "      <Icon />")?;
+
+        writeln!(writer, "      <SplitTimes>")?;
+        let [real_split, game_split] = TimingMethod::ALL.map(|method| {
+            if segment.is_skipped(method) {
+                None
+            } else {
+                segment.end_ms(method)
+            }
+        });
+        write_times(
+            &mut writer,
+            "        ",
+            "SplitTime",
+            r#"name="Personal Best""#,
+            real_split,
+            game_split,
+        )?;
+        writeln!(writer, "      </SplitTimes>")?;
+
+        write_times(
+            &mut writer,
+            "      ",
+            "BestSegmentTime",
+            "",
+            segment.shortest_duration_ms(TimingMethod::RealTime),
+            segment.shortest_duration_ms(TimingMethod::GameTime),
+        )?;
+
+        writeln!(writer, "      <SegmentHistory>")?;
+        for (real, game) in real.iter().zip(&game) {
+            // Attempts that were reset before reaching the segment have no history for it, while
+            // skipped segments are recorded without a time.
+            let reached = real.segments[index].is_some()
+                || game.segments[index].is_some()
+                || real
+                    .furthest_segment()
+                    .is_some_and(|furthest| furthest > index);
+            if reached {
+                write_times(
+                    &mut writer,
+                    "        ",
+                    "Time",
+                    &format!(r#"id="{}""#, real.number),
+                    real.segments[index],
+                    game.segments[index],
+                )?;
+            }
+        }
+        writeln!(writer, "      </SegmentHistory>")?;
+        writeln!(writer, "    </Segment>")?;
+    }
+    writeln!(writer, "  </Segments>")?;
+    writeln!(writer, "  <AutoSplitterSettings />")?;
+    writeln!(writer, "</Run>")
+}
+
+/// Converts the run into the contents of a LiveSplit splits file (`.lss`).
+pub fn to_string(run: &Run) -> String {
+    let mut buf = Vec::new();
+    // Writing into a Vec can't fail.
+    let _ = write(run, &mut buf);
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+fn write_times<W: Write>(
+    writer: &mut W,
+    indent: &str,
+    tag: &str,
+    attributes: &str,
+    real_time_ms: Option<f64>,
+    game_time_ms: Option<f64>,
+) -> io::Result<()> {
+    let space = if attributes.is_empty() { "" } else { " " };
+    if real_time_ms.is_none() && game_time_ms.is_none() {
+        return writeln!(writer, "{indent}<{tag}{space}{attributes} />");
+    }
+    writeln!(writer, "{indent}<{tag}{space}{attributes}>")?;
+    if let Some(time) = real_time_ms {
+        writeln!(writer, "{indent}  <RealTime>{}</RealTime>", LssTime(time))?;
+    }
+    if let Some(time) = game_time_ms {
+        writeln!(writer, "{indent}  <GameTime>{}</GameTime>", LssTime(time))?;
+    }
+    writeln!(writer, "{indent}</{tag}>")
+}
+
+/// Formats a time in milliseconds the way LiveSplit stores it, such as `01:02:03.4560000`.
+struct LssTime(f64);
+
+impl fmt::Display for LssTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0.0 {
+            f.write_str("-")?;
+        }
+        let ticks = (self.0.abs() * 10_000.0).round() as u64;
+        let total_seconds = ticks / 10_000_000;
+        let (days, hours) = (total_seconds / 86_400, total_seconds / 3600 % 24);
+        let (minutes, seconds) = (total_seconds / 60 % 60, total_seconds % 60);
+        if days > 0 {
+            write!(f, "{days}.")?;
+        }
+        write!(
+            f,
+            "{hours:02}:{minutes:02}:{seconds:02}.{:07}",
+            ticks % 10_000_000
+        )
+    }
+}
+
+/// Escapes the characters that have a special meaning in XML.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+        while let Some(pos) = rest.find(['<', '>', '&', '"', '\'']) {
+            f.write_str(&rest[..pos])?;
+            f.write_str(match rest.as_bytes()[pos] {
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'&' => "&amp;",
+                b'"' => "&quot;",
+                _ => "&apos;",
+            })?;
+            rest = &rest[pos + 1..];
+        }
+        f.write_str(rest)
+    }
+}
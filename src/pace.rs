@@ -0,0 +1,248 @@
+//! The pace module compares a live attempt against a Run, such as a personal best downloaded from
+//! splits.io. The split times of the live attempt are provided one at a time as the runner
+//! splits.
+//!
+//! ```no_run
+//! # use splits_io_api::{pace::PaceTracker, Client, Run, TimingMethod};
+//! # async fn track(client: &Client) -> Result<(), splits_io_api::Error> {
+//! let pb = Run::get(client, "4cg", false).await?;
+//! let mut tracker = PaceTracker::new(&pb, TimingMethod::RealTime);
+//!
+//! let result = tracker.split(61_500.0);
+//! println!("{:?} ({:?})", result.delta_ms, result.status);
+//! println!("Projected: {:?}", tracker.projected_final_time_ms());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Run, TimingMethod};
+
+/// How a split compares against the comparison, similar to the colors LiveSplit uses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SplitStatus {
+    /// The segment was faster than the best segment.
+    Gold,
+    /// Ahead of the comparison and gaining time on the segment.
+    AheadGaining,
+    /// Ahead of the comparison, but losing time on the segment.
+    AheadLosing,
+    /// Behind the comparison, but gaining time on the segment.
+    BehindGaining,
+    /// Behind the comparison and losing time on the segment.
+    BehindLosing,
+    /// The split was skipped.
+    Skipped,
+    /// There is no comparison for the split.
+    NoComparison,
+}
+
+/// The result of a single split of the live attempt.
+#[derive(Debug, Copy, Clone)]
+pub struct SplitResult {
+    /// The position of the segment that was split.
+    pub index: usize,
+    /// The split time in milliseconds. This is `None` if the split was skipped.
+    pub split_time_ms: Option<f64>,
+    /// The difference between the split time and the comparison's split time in milliseconds.
+    pub delta_ms: Option<f64>,
+    /// The duration of the segment in milliseconds, including any skipped segments before it.
+    pub segment_time_ms: Option<f64>,
+    /// The difference between the segment's duration and the comparison's duration of the same
+    /// segment in milliseconds.
+    pub segment_delta_ms: Option<f64>,
+    /// Whether the segment is faster than the best segment.
+    pub gold: bool,
+    /// How the split compares against the comparison.
+    pub status: SplitStatus,
+}
+
+/// Tracks a live attempt and compares it against a Run.
+#[derive(Debug, Clone)]
+pub struct PaceTracker<'a> {
+    run: &'a Run,
+    method: TimingMethod,
+    comparison: Vec<Option<f64>>,
+    splits: Vec<SplitResult>,
+}
+
+impl<'a> PaceTracker<'a> {
+    /// Creates a tracker that compares against the split times of the run itself for the given
+    /// timing method.
+    pub fn new(run: &'a Run, method: TimingMethod) -> Self {
+        let comparison = run
+            .segments
+            .iter()
+            .map(|segment| {
+                if segment.is_skipped(method) {
+                    None
+                } else {
+                    segment.end_ms(method)
+                }
+            })
+            .collect();
+        Self::with_comparison(run, method, comparison)
+    }
+
+    /// Creates a tracker that compares against the given split times in milliseconds, such as a
+    /// comparison derived with [`Run::comparison`]. The run is still used for the best segments.
+    pub const fn with_comparison(
+        run: &'a Run,
+        method: TimingMethod,
+        comparison: Vec<Option<f64>>,
+    ) -> Self {
+        Self {
+            run,
+            method,
+            comparison,
+            splits: Vec::new(),
+        }
+    }
+
+    /// The results of all the splits so far.
+    pub fn splits(&self) -> &[SplitResult] {
+        &self.splits
+    }
+
+    /// The position of the segment that is currently being run. This is equal to the number of
+    /// segments if the attempt is finished.
+    pub const fn current_index(&self) -> usize {
+        self.splits.len()
+    }
+
+    /// Whether all the segments were split.
+    pub const fn is_finished(&self) -> bool {
+        self.splits.len() >= self.run.segments.len()
+    }
+
+    /// Splits the current segment at the given time in milliseconds. Splitting a finished attempt
+    /// returns the result of the final split again.
+    pub fn split(&mut self, time_ms: f64) -> SplitResult {
+        self.push(Some(time_ms))
+    }
+
+    /// Skips the current segment, so its duration is included in the next segment.
+    pub fn skip_split(&mut self) -> SplitResult {
+        self.push(None)
+    }
+
+    /// Undoes the last split or skipped split.
+    pub fn undo_split(&mut self) {
+        self.splits.pop();
+    }
+
+    /// Clears all the splits to start a new attempt.
+    pub fn reset(&mut self) {
+        self.splits.clear();
+    }
+
+    /// The delta at the last split that wasn't skipped, in milliseconds.
+    pub fn current_delta_ms(&self) -> Option<f64> {
+        self.splits.iter().rev().find_map(|split| split.delta_ms)
+    }
+
+    /// The difference in milliseconds between the given current time and the comparison's split
+    /// time of the current segment.
+    pub fn live_delta_ms(&self, current_time_ms: f64) -> Option<f64> {
+        Some(current_time_ms - (*self.comparison.get(self.current_index())?)?)
+    }
+
+    /// The difference in milliseconds between how long the current segment has taken so far and
+    /// the comparison's duration of the same segment.
+    pub fn live_segment_delta_ms(&self, current_time_ms: f64) -> Option<f64> {
+        let index = self.current_index();
+        let (previous_index, previous_ms) = self.previous_split(index);
+        let comparison_ms = self.comparison_segment_ms(previous_index, index)?;
+        Some(current_time_ms - previous_ms - comparison_ms)
+    }
+
+    /// The projected final time in milliseconds, assuming the rest of the attempt matches the
+    /// comparison. Before the first split, this is the comparison's final time.
+    pub fn projected_final_time_ms(&self) -> Option<f64> {
+        let final_ms = (*self.comparison.last()?)?;
+        Some(final_ms + self.current_delta_ms().unwrap_or_default())
+    }
+
+    fn push(&mut self, time_ms: Option<f64>) -> SplitResult {
+        let index = self.current_index();
+        if index >= self.run.segments.len() {
+            return self.splits.last().copied().unwrap_or(SplitResult {
+                index,
+                split_time_ms: None,
+                delta_ms: None,
+                segment_time_ms: None,
+                segment_delta_ms: None,
+                gold: false,
+                status: SplitStatus::NoComparison,
+            });
+        }
+
+        let (previous_index, previous_ms) = self.previous_split(index);
+        let comparison_ms = self.comparison.get(index).copied().flatten();
+        let delta_ms = time_ms
+            .zip(comparison_ms)
+            .map(|(time, comparison)| time - comparison);
+        let segment_time_ms = time_ms.map(|time| time - previous_ms);
+        let segment_delta_ms = segment_time_ms
+            .zip(self.comparison_segment_ms(previous_index, index))
+            .map(|(segment, comparison)| segment - comparison);
+
+        let best_ms = self.run.segments[previous_index.map_or(0, |previous| previous + 1)..=index]
+            .iter()
+            .map(|segment| segment.shortest_duration_ms(self.method))
+            .sum::<Option<f64>>();
+        let gold = segment_time_ms
+            .zip(best_ms)
+            .is_some_and(|(segment, best)| segment < best);
+
+        let status = match (time_ms, delta_ms) {
+            (None, _) => SplitStatus::Skipped,
+            _ if gold => SplitStatus::Gold,
+            (_, None) => SplitStatus::NoComparison,
+            (_, Some(delta)) => {
+                let gaining = match segment_delta_ms {
+                    Some(segment_delta) => segment_delta < 0.0,
+                    None => delta < self.current_delta_ms().unwrap_or_default(),
+                };
+                match (delta < 0.0, gaining) {
+                    (true, true) => SplitStatus::AheadGaining,
+                    (true, false) => SplitStatus::AheadLosing,
+                    (false, true) => SplitStatus::BehindGaining,
+                    (false, false) => SplitStatus::BehindLosing,
+                }
+            }
+        };
+
+        let result = SplitResult {
+            index,
+            split_time_ms: time_ms,
+            delta_ms,
+            segment_time_ms,
+            segment_delta_ms,
+            gold,
+            status,
+        };
+        self.splits.push(result);
+        result
+    }
+
+    /// The position and split time of the last split before the given segment that wasn't
+    /// skipped. The start of the run is used if there is none.
+    fn previous_split(&self, index: usize) -> (Option<usize>, f64) {
+        self.splits[..index.min(self.splits.len())]
+            .iter()
+            .rev()
+            .find_map(|split| Some((Some(split.index), split.split_time_ms?)))
+            .unwrap_or((None, 0.0))
+    }
+
+    /// The comparison's duration from the end of the previous segment to the end of the given
+    /// segment.
+    fn comparison_segment_ms(&self, previous_index: Option<usize>, index: usize) -> Option<f64> {
+        let end_ms = (*self.comparison.get(index)?)?;
+        let start_ms = match previous_index {
+            Some(previous) => (*self.comparison.get(previous)?)?,
+            None => 0.0,
+        };
+        Some(end_ms - start_ms)
+    }
+}
@@ -0,0 +1,241 @@
+//! The page module handles the pagination of the API's list endpoints. Lists are retrieved one
+//! page at a time, either as individual pages or as a stream that follows the links to the next
+//! page as the items are needed.
+//!
+//! ```no_run
+//! # use splits_io_api::{page::PageSettings, Category, Client};
+//! # use futures_util::TryStreamExt;
+//! # async fn list(client: &Client, category: &Category) -> Result<(), splits_io_api::Error> {
+//! let settings = PageSettings {
+//!     per_page: 50,
+//!     prefetch: true,
+//! };
+//! let mut runs = std::pin::pin!(category.runs_stream(client, settings));
+//! while let Some(run) = runs.try_next().await? {
+//!     println!("{:?}", run.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{get_response, Client, Error};
+use futures_util::Stream;
+use reqwest::{header::LINK, Url};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The settings for retrieving a list one page at a time.
+#[derive(Debug, Copy, Clone)]
+pub struct PageSettings {
+    /// The number of items to request per page.
+    pub per_page: u32,
+    /// Whether to request the next page while the items of the current page are still being
+    /// consumed. The next page is only known once the current page arrived, so this requests a
+    /// single page ahead. This never requests pages past the last page.
+    pub prefetch: bool,
+}
+
+impl Default for PageSettings {
+    fn default() -> Self {
+        Self {
+            per_page: 100,
+            prefetch: false,
+        }
+    }
+}
+
+/// A single page of a list.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// The items on the page.
+    pub items: Vec<T>,
+    /// The number of the page. (This value starts at 1.)
+    pub page: u32,
+    /// The URL of the next page, if there is one. Links to other origins than the client's base
+    /// URL are not followed, as the access token would be sent along, so they end the list.
+    pub next: Option<Url>,
+    /// The number of the last page, if the API reported it.
+    pub last_page: Option<u32>,
+    /// The total number of items in the list, if the API reported it.
+    pub total: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Whether there are more pages after this page.
+    pub const fn has_next(&self) -> bool {
+        self.next.is_some()
+    }
+}
+
+/// Gets a single page of the list at the URL. The items are taken out of the response with the
+/// given function.
+pub(crate) async fn get_page<W, T>(
+    client: &Client,
+    mut url: Url,
+    page: u32,
+    per_page: u32,
+    items: fn(W) -> Vec<T>,
+) -> Result<Page<T>, Error>
+where
+    W: serde::de::DeserializeOwned,
+{
+    url.query_pairs_mut()
+        .append_pair("page", &page.to_string())
+        .append_pair("per_page", &per_page.to_string());
+    get_page_at(client, url, items).await
+}
+
+/// Gets the page of a list at the URL, which already includes the page to request.
+async fn get_page_at<W, T>(
+    client: &Client,
+    url: Url,
+    items: fn(W) -> Vec<T>,
+) -> Result<Page<T>, Error>
+where
+    W: serde::de::DeserializeOwned,
+{
+    let page = page_number(&url).unwrap_or(1);
+
+    let response = get_response(client, client.client.get(url)).await?;
+    let headers = response.headers();
+    let links = headers
+        .get(LINK)
+        .and_then(|link| link.to_str().ok())
+        .map(parse_links)
+        .unwrap_or_default();
+    let next = links
+        .iter()
+        .find(|(rel, _)| rel == "next")
+        .map(|(_, url)| url.clone())
+        .filter(|url| url.origin() == client.base_url().origin());
+    let last_page = links
+        .iter()
+        .find(|(rel, _)| rel == "last")
+        .and_then(|(_, url)| page_number(url));
+    let total = headers
+        .get("total")
+        .and_then(|total| total.to_str().ok()?.parse().ok());
+
+    let wrapper: W = response
+        .json()
+        .await
+        .map_err(|source| Error::Download { source })?;
+
+    Ok(Page {
+        items: items(wrapper),
+        page,
+        next,
+        last_page,
+        total,
+    })
+}
+
+/// Streams all the items of the list at the URL, following the link to the next page of each
+/// page. The stream ends after the first error.
+pub(crate) fn stream<'a, W, T>(
+    client: &'a Client,
+    mut url: Url,
+    settings: PageSettings,
+    items: fn(W) -> Vec<T>,
+) -> impl Stream<Item = Result<T, Error>> + 'a
+where
+    W: serde::de::DeserializeOwned + 'a,
+    T: 'a,
+{
+    url.query_pairs_mut()
+        .append_pair("page", "1")
+        .append_pair("per_page", &settings.per_page.max(1).to_string());
+
+    let fetch = move |url| get_page_at(client, url, items);
+    PageStream {
+        pending: Some(Box::pin(fetch(url))),
+        fetch,
+        items: VecDeque::new(),
+        error: None,
+        prefetch: settings.prefetch,
+    }
+}
+
+/// The stream of the items of a list. The page that is requested next is kept alongside the items
+/// of the current page, so it can be requested ahead if prefetching.
+struct PageStream<F, Fut, T> {
+    fetch: F,
+    pending: Option<Pin<Box<Fut>>>,
+    items: VecDeque<T>,
+    error: Option<Error>,
+    prefetch: bool,
+}
+
+// The pending page is boxed, so nothing in the stream is pinned in place.
+impl<F, Fut, T> Unpin for PageStream<F, Fut, T> {}
+
+impl<F, Fut, T> Stream for PageStream<F, Fut, T>
+where
+    F: FnMut(Url) -> Fut,
+    Fut: Future<Output = Result<Page<T>, Error>>,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while let Some(pending) = &mut this.pending {
+            if !this.prefetch && !this.items.is_empty() {
+                break;
+            }
+            let Poll::Ready(page) = pending.as_mut().poll(cx) else {
+                break;
+            };
+            this.pending = None;
+            match page {
+                Ok(page) => {
+                    // Endpoints that don't paginate don't send a Link header, so they are treated
+                    // as a single page. An empty page ends the list as well, so a server that
+                    // keeps linking to further pages can't make the stream go on forever.
+                    if let Some(next) = page.next.filter(|_| !page.items.is_empty()) {
+                        this.pending = Some(Box::pin((this.fetch)(next)));
+                    }
+                    this.items.extend(page.items);
+                }
+                Err(error) => this.error = Some(error),
+            }
+        }
+
+        if let Some(item) = this.items.pop_front() {
+            Poll::Ready(Some(Ok(item)))
+        } else if let Some(error) = this.error.take() {
+            Poll::Ready(Some(Err(error)))
+        } else if this.pending.is_none() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// The number of the page that the URL of a page of a list refers to.
+fn page_number(url: &Url) -> Option<u32> {
+    url.query_pairs()
+        .find_map(|(key, value)| (key == "page").then(|| value.parse().ok())?)
+}
+
+/// Parses a Link header such as `<https://splits.io/api/v4/runs?page=2>; rel="next"` into the
+/// relation types and their URLs.
+fn parse_links(header: &str) -> Vec<(String, Url)> {
+    header
+        .split(',')
+        .filter_map(|link| {
+            let mut parts = link.split(';');
+            let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+            let url = Url::parse(url).ok()?;
+            let rel = parts.find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                (key.trim() == "rel").then(|| value.trim().trim_matches('"').to_owned())
+            })?;
+            Some((rel, url))
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+//! The profile module combines everything splits.io knows about a Runner, such as for showing a
+//! page about the runner.
+
+use crate::{history, run, Category, Client, Error, Game, Run, Runner, TimingMethod};
+use futures_util::{stream, StreamExt, TryStreamExt};
+
+/// A Runner together with their personal bests, games, categories and runs.
+#[derive(Debug)]
+pub struct RunnerProfile {
+    /// The runner.
+    pub runner: Runner,
+    /// The personal best Runs of the runner.
+    pub pbs: Vec<Run>,
+    /// The Games the runner has runs of.
+    pub games: Vec<Game>,
+    /// The Categories the runner has runs of.
+    pub categories: Vec<Category>,
+    /// All the Runs of the runner. These don't include their history unless it was loaded with
+    /// [`load_histories`](Self::load_histories).
+    pub runs: Vec<Run>,
+}
+
+/// A personal best of a runner, linked to its Game and Category.
+#[derive(Debug, Copy, Clone)]
+pub struct ProfilePb<'a> {
+    /// The personal best Run.
+    pub run: &'a Run,
+    /// The Game of the run from the runner's games.
+    pub game: Option<&'a Game>,
+    /// The Category of the run from the runner's categories.
+    pub category: Option<&'a Category>,
+}
+
+impl RunnerProfile {
+    /// The personal bests of the runner, linked to the Games and Categories of the runner.
+    pub fn linked_pbs(&self) -> impl Iterator<Item = ProfilePb<'_>> {
+        self.pbs.iter().map(|run| ProfilePb {
+            run,
+            game: run
+                .game
+                .as_ref()
+                .and_then(|game| self.games.iter().find(|g| g.id == game.id)),
+            category: run
+                .category
+                .as_ref()
+                .and_then(|category| self.categories.iter().find(|c| c.id == category.id)),
+        })
+    }
+
+    /// The total number of attempts recorded in all the runs of the runner.
+    pub fn total_attempts(&self) -> u64 {
+        self.runs
+            .iter()
+            .map(|run| {
+                run.attempts.map_or_else(
+                    || run.histories.as_ref().map_or(0, Vec::len) as u64,
+                    u64::from,
+                )
+            })
+            .sum()
+    }
+
+    /// Retrieves the history of all the runs of the runner, which the runs listed for a runner
+    /// don't include. This makes a request for every run, with at most the given number of
+    /// requests at a time. A concurrency of 0 is treated as 1.
+    pub async fn load_histories(
+        &mut self,
+        client: &Client,
+        concurrency: usize,
+    ) -> Result<(), Error> {
+        let runs = stream::iter(&self.runs)
+            .map(|listed| async move {
+                match (&listed.histories, &listed.id) {
+                    (None, Some(id)) => run::get(client, id, true).await,
+                    _ => Ok(listed.clone()),
+                }
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+        self.runs = runs;
+        Ok(())
+    }
+
+    /// Estimates how long the runner has spent on attempts in milliseconds, based on the history
+    /// of their runs. Attempts that were reset count with the segments they completed, so the
+    /// time spent on the segment they were reset in is missing. The runs of a profile retrieved
+    /// from splits.io don't include their history, so this is 0 until
+    /// [`load_histories`](Self::load_histories) is called.
+    pub fn estimated_playtime_ms(&self) -> f64 {
+        self.runs
+            .iter()
+            .flat_map(|run| history::attempts(run, TimingMethod::RealTime))
+            .map(|attempt| {
+                attempt
+                    .duration_ms
+                    .unwrap_or_else(|| attempt.segments.iter().flatten().sum())
+            })
+            .sum()
+    }
+}
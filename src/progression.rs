@@ -0,0 +1,90 @@
+//! The progression module reconstructs how a runner improved over their attempts of a Run, by
+//! walking through the history in the order the attempts happened.
+
+use crate::{history, Run, TimingMethod};
+
+/// An attempt that set a new personal best.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PersonalBest {
+    /// The attempt that set the personal best.
+    pub attempt_number: u32,
+    /// The final time of the attempt in milliseconds.
+    pub time_ms: f64,
+    /// The time in milliseconds that was saved compared to the previous personal best. This is
+    /// `None` for the first finished attempt.
+    pub time_saved_ms: Option<f64>,
+}
+
+/// An attempt that set a new best segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldEvent {
+    /// The index of the segment within the run. (This value starts at 0.)
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// The attempt that set the best segment.
+    pub attempt_number: u32,
+    /// The duration of the segment in milliseconds.
+    pub duration_ms: f64,
+    /// The time in milliseconds that was saved compared to the previous best segment. This is
+    /// `None` for the first time the segment was completed.
+    pub time_saved_ms: Option<f64>,
+}
+
+impl Run {
+    /// Determines all the attempts that set a new personal best for the given timing method, in
+    /// the order they happened. This requires the run to be retrieved with its history.
+    pub fn pb_progression(&self, method: TimingMethod) -> Vec<PersonalBest> {
+        let last_segment = self.segments.len().checked_sub(1);
+        let mut pb: Option<f64> = None;
+
+        history::attempts(self, method)
+            .iter()
+            .filter_map(|attempt| {
+                let time_ms = attempt
+                    .duration_ms
+                    .or_else(|| attempt.split_time_ms(last_segment?))?;
+                if pb.is_some_and(|pb| time_ms >= pb) {
+                    return None;
+                }
+                let time_saved_ms = pb.map(|pb| pb - time_ms);
+                pb = Some(time_ms);
+                Some(PersonalBest {
+                    attempt_number: attempt.number,
+                    time_ms,
+                    time_saved_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Determines all the attempts that set a new best segment for the given timing method,
+    /// ordered by when they happened and then by segment. This requires the run to be retrieved
+    /// with its history. Segments directly after a skipped segment are ignored, as their
+    /// durations include the skipped segment.
+    pub fn gold_timeline(&self, method: TimingMethod) -> Vec<GoldEvent> {
+        let mut golds = vec![None::<f64>; self.segments.len()];
+        let mut timeline = Vec::new();
+
+        for attempt in history::attempts(self, method) {
+            for (index, (segment, gold)) in self.segments.iter().zip(&mut golds).enumerate() {
+                let Some(duration_ms) = attempt.segment_duration_ms(index) else {
+                    continue;
+                };
+                if gold.is_some_and(|gold| duration_ms >= gold) {
+                    continue;
+                }
+                timeline.push(GoldEvent {
+                    segment_number: segment.segment_number,
+                    name: segment.name.clone(),
+                    attempt_number: attempt.number,
+                    duration_ms,
+                    time_saved_ms: gold.map(|gold| gold - duration_ms),
+                });
+                *gold = Some(duration_ms);
+            }
+        }
+
+        timeline
+    }
+}
@@ -0,0 +1,264 @@
+//! The query module filters and sorts the Runs of a Category or Runner. The splits.io API doesn't
+//! filter or sort run listings itself, so only the page size is sent to the API, while the filters
+//! and the sorting are applied to the Runs as they are retrieved. Queries without sorting stop
+//! requesting pages once the limit is reached.
+//!
+//! ```no_run
+//! # use splits_io_api::{query::{RunQuery, SortBy}, Client, Runner, TimingMethod};
+//! # async fn query(client: &Client, runner: &Runner) -> Result<(), splits_io_api::Error> {
+//! let query = RunQuery::new()
+//!     .game("tww")
+//!     .has_video(true)
+//!     .created_after("2020-01-01")
+//!     .sort_by(SortBy::Duration(TimingMethod::RealTime))
+//!     .limit(10);
+//!
+//! for run in runner.query_runs(client, &query).await? {
+//!     println!("{:?} {:?}", run.id, run.realtime_duration_ms);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{page::PageSettings, Error, Run, TimingMethod};
+use futures_util::{Stream, StreamExt};
+use std::{cmp::Ordering, pin::pin};
+
+/// What to sort Runs by.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SortBy {
+    /// Sort by the duration of the runs for the given timing method. Runs without a duration come
+    /// last.
+    Duration(TimingMethod),
+    /// Sort by when the runs were uploaded.
+    CreatedAt,
+    /// Sort by when the runs were last modified.
+    UpdatedAt,
+}
+
+/// A filter, sorting and limit for listing Runs.
+#[derive(Debug, Clone, Default)]
+pub struct RunQuery {
+    game: Option<Box<str>>,
+    category: Option<Box<str>>,
+    program: Option<Box<str>>,
+    has_video: Option<bool>,
+    created_after: Option<Box<str>>,
+    created_before: Option<Box<str>>,
+    updated_after: Option<Box<str>>,
+    updated_before: Option<Box<str>>,
+    sort_by: Option<SortBy>,
+    descending: bool,
+    limit: Option<usize>,
+}
+
+impl RunQuery {
+    /// Creates a query that matches all Runs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches Runs of the game with the given ID or shortened title.
+    pub fn game(mut self, game: &str) -> Self {
+        self.game = Some(game.into());
+        self
+    }
+
+    /// Only matches Runs of the category with the given ID or name. Names are compared without
+    /// regard to case.
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Only matches Runs recorded with the given timer, such as `livesplit`.
+    pub fn program(mut self, program: &str) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    /// Only matches Runs that do or don't have a video.
+    pub const fn has_video(mut self, has_video: bool) -> Self {
+        self.has_video = Some(has_video);
+        self
+    }
+
+    /// Only matches Runs uploaded at or after the given ISO 8601 date or time, such as
+    /// `2020-01-01` or `2020-01-01T12:00:00Z`.
+    pub fn created_after(mut self, date: &str) -> Self {
+        self.created_after = Some(date.into());
+        self
+    }
+
+    /// Only matches Runs uploaded before the given ISO 8601 date or time.
+    pub fn created_before(mut self, date: &str) -> Self {
+        self.created_before = Some(date.into());
+        self
+    }
+
+    /// Only matches Runs last modified at or after the given ISO 8601 date or time.
+    pub fn updated_after(mut self, date: &str) -> Self {
+        self.updated_after = Some(date.into());
+        self
+    }
+
+    /// Only matches Runs last modified before the given ISO 8601 date or time.
+    pub fn updated_before(mut self, date: &str) -> Self {
+        self.updated_before = Some(date.into());
+        self
+    }
+
+    /// Sorts the Runs in ascending order.
+    pub const fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = Some(sort_by);
+        self.descending = false;
+        self
+    }
+
+    /// Sorts the Runs in descending order.
+    pub const fn sort_by_descending(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = Some(sort_by);
+        self.descending = true;
+        self
+    }
+
+    /// Limits the number of Runs to return.
+    pub const fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether the Run matches the filters of the query.
+    pub fn matches(&self, run: &Run) -> bool {
+        if let Some(game) = &self.game {
+            let Some(run_game) = &run.game else {
+                return false;
+            };
+            if run_game.id != *game && run_game.shortname.as_ref() != Some(game) {
+                return false;
+            }
+        }
+        if let Some(category) = &self.category {
+            let Some(run_category) = &run.category else {
+                return false;
+            };
+            if run_category.id != *category && !run_category.name.eq_ignore_ascii_case(category) {
+                return false;
+            }
+        }
+        if self
+            .program
+            .as_ref()
+            .is_some_and(|program| !run.program.eq_ignore_ascii_case(program))
+        {
+            return false;
+        }
+        if self.has_video.is_some_and(|has_video| {
+            has_video != run.video_url.as_deref().is_some_and(|url| !url.is_empty())
+        }) {
+            return false;
+        }
+        within(
+            &run.created_at,
+            self.created_after.as_deref(),
+            self.created_before.as_deref(),
+        ) && within(
+            &run.updated_at,
+            self.updated_after.as_deref(),
+            self.updated_before.as_deref(),
+        )
+    }
+
+    /// Applies the filters, sorting and limit of the query to the Runs.
+    pub fn apply(&self, runs: impl IntoIterator<Item = Run>) -> Vec<Run> {
+        let mut runs = runs
+            .into_iter()
+            .filter(|run| self.matches(run))
+            .collect::<Vec<_>>();
+        self.sort(&mut runs);
+        if let Some(limit) = self.limit {
+            runs.truncate(limit);
+        }
+        runs
+    }
+
+    /// Retrieves the Runs from the stream of Runs that match the query.
+    pub(crate) async fn collect(
+        &self,
+        runs: impl Stream<Item = Result<Run, Error>>,
+    ) -> Result<Vec<Run>, Error> {
+        let mut runs = pin!(runs);
+        let mut matching = Vec::new();
+        while let Some(run) = runs.next().await {
+            let run = run?;
+            if self.matches(&run) {
+                matching.push(run);
+                if self.sort_by.is_none() && self.limit.is_some_and(|limit| matching.len() >= limit)
+                {
+                    break;
+                }
+            }
+        }
+        self.sort(&mut matching);
+        if let Some(limit) = self.limit {
+            matching.truncate(limit);
+        }
+        Ok(matching)
+    }
+
+    /// The page settings for retrieving the Runs for the query.
+    pub(crate) fn page_settings(&self) -> PageSettings {
+        let mut settings = PageSettings::default();
+        // Without any client-side filtering, exactly as many Runs as needed can be requested.
+        let unfiltered = self.matches_all();
+        if let Some(limit) = self.limit.filter(|_| unfiltered && self.sort_by.is_none()) {
+            settings.per_page = settings.per_page.min(limit.max(1) as u32);
+            settings.prefetch = false;
+        }
+        settings
+    }
+
+    const fn matches_all(&self) -> bool {
+        self.game.is_none()
+            && self.category.is_none()
+            && self.program.is_none()
+            && self.has_video.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.updated_after.is_none()
+            && self.updated_before.is_none()
+    }
+
+    fn sort(&self, runs: &mut [Run]) {
+        let Some(sort_by) = self.sort_by else {
+            return;
+        };
+        let descending = self.descending;
+        runs.sort_by(|a, b| {
+            let ordering = match sort_by {
+                SortBy::Duration(method) => {
+                    match (a.duration_ms(method), b.duration_ms(method)) {
+                        (Some(a), Some(b)) => a.total_cmp(&b),
+                        // Runs without a duration come last regardless of the order.
+                        (Some(_), None) => return Ordering::Less,
+                        (None, Some(_)) => return Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                }
+                SortBy::CreatedAt => a.created_at.cmp(&b.created_at),
+                SortBy::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+/// Whether the ISO 8601 date is within the range. Dates in the same format can be compared as
+/// strings.
+fn within(date: &str, after: Option<&str>, before: Option<&str>) -> bool {
+    after.is_none_or(|after| date >= after) && before.is_none_or(|before| date < before)
+}
@@ -10,7 +10,6 @@ use crate::{
     },
     Attachment, ChatMessage, Client, Entry, Error, Race, Visibility,
 };
-use reqwest::Url;
 use std::ops::Deref;
 use uuid::Uuid;
 
@@ -123,7 +122,7 @@ impl Attachment {
 /// Gets all the currently active Races on splits.io.
 pub async fn get_active(client: &Client) -> Result<Vec<Race>, Error> {
     let ContainsRaces { races } =
-        get_json(client, client.client.get("https://splits.io/api/v4/races")).await?;
+        get_json(client, client.client.get(client.api_url("races"))).await?;
 
     Ok(races)
 }
@@ -132,7 +131,7 @@ pub async fn get_active(client: &Client) -> Result<Vec<Race>, Error> {
 
 /// Gets a Race by its ID.
 pub async fn get(client: &Client, id: Uuid) -> Result<Race, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut()
         .unwrap()
         .push(id.hyphenated().encode_lower(&mut Uuid::encode_buffer()));
@@ -210,10 +209,7 @@ pub struct UpdateSettings<'a> {
 pub async fn create(client: &Client, settings: Settings<'_>) -> Result<Race, Error> {
     let ContainsRace { race } = get_json(
         client,
-        client
-            .client
-            .post("https://splits.io/api/v4/races")
-            .json(&settings),
+        client.client.post(client.api_url("races")).json(&settings),
     )
     .await?;
 
@@ -226,7 +222,7 @@ pub async fn update(
     id: Uuid,
     settings: UpdateSettings<'_>,
 ) -> Result<Race, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut()
         .unwrap()
         .push(id.hyphenated().encode_lower(&mut Uuid::encode_buffer()));
@@ -238,7 +234,7 @@ pub async fn update(
 
 /// Gets all of the entries for a Race.
 pub async fn get_entries(client: &Client, id: Uuid) -> Result<Vec<Entry>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         id.hyphenated().encode_lower(&mut Uuid::encode_buffer()),
         "entries",
@@ -251,7 +247,7 @@ pub async fn get_entries(client: &Client, id: Uuid) -> Result<Vec<Entry>, Error>
 
 /// Gets the entry in a Race that is associated with the current user.
 pub async fn get_entry(client: &Client, id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         id.hyphenated().encode_lower(&mut Uuid::encode_buffer()),
         "entry",
@@ -290,7 +286,7 @@ pub async fn join(
     join_as: JoinAs<'_>,
     join_token: Option<&str>,
 ) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -315,7 +311,7 @@ pub async fn join(
 
 /// Leaves the Race for the given entry.
 pub async fn leave(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<(), Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -353,7 +349,7 @@ struct ForfeitState {
 
 /// Declares the given entry as ready for a Race.
 pub async fn ready_up(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -379,7 +375,7 @@ pub async fn ready_up(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<
 
 /// Undoes a ready for the given entry in a Race.
 pub async fn unready(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -403,7 +399,7 @@ pub async fn unready(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<E
 
 /// Finishes the Race for the given entry.
 pub async fn finish(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -429,7 +425,7 @@ pub async fn finish(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<En
 
 /// Undoes a finish for the given entry in a Race.
 pub async fn undo_finish(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -453,7 +449,7 @@ pub async fn undo_finish(client: &Client, race_id: Uuid, entry_id: Uuid) -> Resu
 
 /// Forfeits the Race for the given entry.
 pub async fn forfeit(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -479,7 +475,7 @@ pub async fn forfeit(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<E
 
 /// Undoes a forfeit for the given entry in a Race.
 pub async fn undo_forfeit(client: &Client, race_id: Uuid, entry_id: Uuid) -> Result<Entry, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         race_id
             .hyphenated()
@@ -503,7 +499,7 @@ pub async fn undo_forfeit(client: &Client, race_id: Uuid, entry_id: Uuid) -> Res
 
 /// Gets all of the chat messages for a Race.
 pub async fn get_chat(client: &Client, id: Uuid) -> Result<Vec<ChatMessage>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         id.hyphenated().encode_lower(&mut Uuid::encode_buffer()),
         "chat",
@@ -530,7 +526,7 @@ pub async fn send_chat_message(
     id: Uuid,
     message: &str,
 ) -> Result<ChatMessage, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/races").unwrap();
+    let mut url = client.api_url("races");
     url.path_segments_mut().unwrap().extend(&[
         id.hyphenated().encode_lower(&mut Uuid::encode_buffer()),
         "chat",
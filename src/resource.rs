@@ -0,0 +1,217 @@
+//! The resource module parses links to splits.io pages, such as the ones users copy from their
+//! browser, into references to the resources they show.
+//!
+//! ```no_run
+//! # use splits_io_api::{resource::{FetchedResource, Resource}, Client};
+//! # async fn open(client: &Client) -> Result<(), splits_io_api::Error> {
+//! if let Some(resource) = Resource::parse("https://splits.io/4cg") {
+//!     if let FetchedResource::Run(run) = resource.fetch(client).await? {
+//!         println!("{:?}", run.realtime_duration_ms);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{category, game, race, run, runner, Category, Client, Error, Game, Race, Run, Runner};
+use reqwest::Url;
+use uuid::Uuid;
+
+/// A reference to a resource on splits.io.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resource {
+    /// A Run with its ID.
+    Run(Box<str>),
+    /// A Runner with their name.
+    Runner(Box<str>),
+    /// A Game with its shortened title or ID.
+    Game(Box<str>),
+    /// A Category of a Game.
+    Category {
+        /// The shortened title or ID of the Game.
+        game: Box<str>,
+        /// The ID of the Category.
+        id: Box<str>,
+    },
+    /// A Race, with the token to join it if the link included one.
+    Race {
+        /// The ID of the Race.
+        id: Uuid,
+        /// The token needed to join the race if it's invite-only or secret.
+        join_token: Option<Box<str>>,
+    },
+}
+
+/// A resource that was retrieved from splits.io.
+#[derive(Debug)]
+pub enum FetchedResource {
+    /// A Run, including its history.
+    Run(Run),
+    /// A Runner.
+    Runner(Runner),
+    /// A Game.
+    Game(Game),
+    /// A Category.
+    Category(Category),
+    /// A Race.
+    Race(Race),
+}
+
+/// Pages at the root of splits.io that aren't runs, even though their path could be a run ID.
+const RESERVED_PATHS: &[&str] = &[
+    "api",
+    "assets",
+    "auth",
+    "cable",
+    "convert",
+    "faq",
+    "games",
+    "login",
+    "logout",
+    "oauth",
+    "packs",
+    "partners",
+    "privacy",
+    "races",
+    "rails",
+    "random",
+    "rivalries",
+    "search",
+    "settings",
+    "signin",
+    "signout",
+    "stats",
+    "subscriptions",
+    "terms",
+    "tools",
+    "upload",
+    "users",
+    "why",
+];
+
+impl Resource {
+    /// Parses a link to a page on splits.io, such as `https://splits.io/4cg` or
+    /// `splits.io/users/cryze92`. Paths like the one of [`Race::path`] are resolved against
+    /// splits.io. Returns `None` if the link isn't a page of a resource on splits.io.
+    pub fn parse(link: &str) -> Option<Self> {
+        let base = default_base();
+        let link = link.trim();
+        let url = Url::parse(link)
+            .ok()
+            .filter(|url| url.has_host())
+            .or_else(|| {
+                if link.starts_with('/') {
+                    base.join(link).ok()
+                } else {
+                    Url::parse(&format!("https://{link}")).ok()
+                }
+            })?;
+        Self::from_url(&url)
+    }
+
+    /// Parses the URL of a page on splits.io into a reference to the resource it shows. Returns
+    /// `None` if the URL isn't a page of a resource on splits.io.
+    pub fn from_url(url: &Url) -> Option<Self> {
+        Self::from_url_with_base(url, &default_base())
+    }
+
+    /// Parses the URL of a page on an instance of splits.io hosted at the given base URL, such as
+    /// `http://localhost:3000`. The scheme and a `www.` prefix of the host are ignored. Returns
+    /// `None` if the URL isn't a page of a resource below the base URL.
+    pub fn from_url_with_base(url: &Url, base: &Url) -> Option<Self> {
+        let host = |url: &Url| {
+            url.host_str()
+                .map(|host| host.trim_start_matches("www.").to_ascii_lowercase())
+        };
+        if host(url)? != host(base)? || url.port() != base.port() {
+            return None;
+        }
+
+        let base_segments = path_segments(base);
+        let segments = path_segments(url);
+        let segments = segments.strip_prefix(base_segments.as_slice())?;
+
+        Some(match segments {
+            [id] if is_run_id(id) && !RESERVED_PATHS.contains(id) => Resource::Run((*id).into()),
+            ["runs", id] => Resource::Run((*id).into()),
+            ["users", name, ..] => Resource::Runner((*name).into()),
+            ["games", game] => Resource::Game((*game).into()),
+            ["games", game, id, ..] => Resource::Category {
+                game: (*game).into(),
+                id: (*id).into(),
+            },
+            ["races", id, ..] => Resource::Race {
+                id: Uuid::parse_str(id).ok()?,
+                join_token: url
+                    .query_pairs()
+                    .find_map(|(key, value)| (key == "join_token").then(|| value.into())),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Creates the URL of the resource's page on splits.io.
+    pub fn url(&self) -> Url {
+        // The default base URL can always be a base.
+        self.url_with_base(&default_base()).unwrap()
+    }
+
+    /// Creates the URL of the resource's page on an instance of splits.io hosted at the given
+    /// base URL, such as [`Client::base_url`]. Returns `None` if the URL can't be a base, such as
+    /// a `mailto:` URL.
+    pub fn url_with_base(&self, base: &Url) -> Option<Url> {
+        let mut url = base.clone();
+        {
+            let mut segments = url.path_segments_mut().ok()?;
+            segments.pop_if_empty();
+            match self {
+                Resource::Run(id) => segments.push(id),
+                Resource::Runner(name) => segments.extend(&["users", name]),
+                Resource::Game(game) => segments.extend(&["games", game]),
+                Resource::Category { game, id } => segments.extend(&["games", game, id]),
+                Resource::Race { id, .. } => segments.extend(&["races", &id.to_string()]),
+            };
+        }
+        if let Resource::Race {
+            join_token: Some(join_token),
+            ..
+        } = self
+        {
+            url.query_pairs_mut().append_pair("join_token", join_token);
+        }
+        Some(url)
+    }
+
+    /// Retrieves the resource from the instance of splits.io the Client accesses. For a resource
+    /// that was parsed with a custom base URL, the Client needs to be created with the same base
+    /// URL with [`Client::with_base_url`].
+    pub async fn fetch(&self, client: &Client) -> Result<FetchedResource, Error> {
+        Ok(match self {
+            Resource::Run(id) => FetchedResource::Run(run::get(client, id, true).await?),
+            Resource::Runner(name) => FetchedResource::Runner(runner::get(client, name).await?),
+            Resource::Game(game) => FetchedResource::Game(game::get(client, game).await?),
+            Resource::Category { id, .. } => {
+                FetchedResource::Category(category::get(client, id).await?)
+            }
+            Resource::Race { id, .. } => FetchedResource::Race(race::get(client, *id).await?),
+        })
+    }
+}
+
+fn default_base() -> Url {
+    Url::parse("https://splits.io").unwrap()
+}
+
+/// Run IDs are numbers in base 36, so they only consist of lowercase letters and digits.
+fn is_run_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+fn path_segments(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
@@ -35,17 +35,21 @@ impl Run {
 
     /// Retrieves the public URL of the run. This may fail if the run is unidentifiable.
     pub fn url(&self) -> Result<Url, Error> {
-        let mut url = Url::parse("https://splits.io").unwrap();
-        url.path_segments_mut()
-            .unwrap()
-            .push(self.id.as_deref().ok_or(Error::UnidentifiableResource)?);
-        Ok(url)
+        let id = self.id.as_deref().ok_or(Error::UnidentifiableResource)?;
+        Ok(run_url(&Url::parse("https://splits.io").unwrap(), id))
+    }
+
+    /// Retrieves the public URL of the run on the instance of splits.io the client accesses. This
+    /// may fail if the run is unidentifiable.
+    pub fn url_for(&self, client: &Client) -> Result<Url, Error> {
+        let id = self.id.as_deref().ok_or(Error::UnidentifiableResource)?;
+        Ok(run_url(client.base_url(), id))
     }
 }
 
 /// Downloads the splits for a Run.
 pub async fn download(client: &Client, id: &str) -> Result<impl Deref<Target = [u8]>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runs").unwrap();
+    let mut url = client.api_url("runs");
     url.path_segments_mut().unwrap().push(id);
 
     get_response(
@@ -63,7 +67,7 @@ pub async fn download(client: &Client, id: &str) -> Result<impl Deref<Target = [
 
 /// Gets a Run.
 pub async fn get(client: &Client, id: &str, historic: bool) -> Result<Run, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runs").unwrap();
+    let mut url = client.api_url("runs");
     url.path_segments_mut().unwrap().push(id);
     if historic {
         url.query_pairs_mut().append_pair("historic", "1");
@@ -118,20 +122,41 @@ impl UploadedRun {
 
     /// Retrieves the public URL of the uploaded run.
     pub fn public_url(&self) -> Url {
-        let mut url = Url::parse("https://splits.io").unwrap();
-        url.path_segments_mut().unwrap().push(&self.id);
-        url
+        run_url(&Url::parse("https://splits.io").unwrap(), &self.id)
+    }
+
+    /// Retrieves the public URL of the uploaded run on the instance of splits.io the client
+    /// accesses.
+    pub fn public_url_for(&self, client: &Client) -> Url {
+        run_url(client.base_url(), &self.id)
     }
 
     /// Retrieves the URL to claim the uploaded run.
     pub fn claim_url(&self) -> Url {
-        let mut url = self.public_url();
+        self.with_claim_token(self.public_url())
+    }
+
+    /// Retrieves the URL to claim the uploaded run on the instance of splits.io the client
+    /// accesses.
+    pub fn claim_url_for(&self, client: &Client) -> Url {
+        self.with_claim_token(self.public_url_for(client))
+    }
+
+    fn with_claim_token(&self, mut url: Url) -> Url {
         url.query_pairs_mut()
             .append_pair("claim_token", &self.claim_token);
         url
     }
 }
 
+/// The URL of the run's page on the instance of splits.io at the base URL.
+fn run_url(base: &Url, id: &str) -> Url {
+    let mut url = base.clone();
+    // The client only accepts base URLs that can be a base.
+    url.path_segments_mut().unwrap().pop_if_empty().push(id);
+    url
+}
+
 /// Handles writing a run to the body of an upload request.
 pub struct RunWriter(Vec<u8>);
 
@@ -150,7 +175,7 @@ pub async fn upload(client: &Client, run: Vec<u8>) -> Result<UploadedRun, Error>
         id,
         claim_token,
         presigned_request: PresignedRequest { uri, fields },
-    } = get_json(client, client.client.post("https://splits.io/api/v4/runs")).await?;
+    } = get_json(client, client.client.post(client.api_url("runs"))).await?;
 
     get_response(
         client,
@@ -3,10 +3,14 @@
 //!
 //! [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#runner)
 
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
 use reqwest::Url;
 
 use crate::{
     get_json,
+    page::{self, Page, PageSettings},
+    profile::RunnerProfile,
+    query::RunQuery,
     wrapper::{
         ContainsCategories, ContainsGames, ContainsPBs, ContainsRunner, ContainsRunners,
         ContainsRuns,
@@ -35,6 +39,38 @@ impl Runner {
         get_runs(client, &self.name).await
     }
 
+    /// Gets the Runs that are associated with the Runner. All the pages of the Runs are
+    /// retrieved, one after another.
+    pub async fn runs_all(&self, client: &Client) -> Result<Vec<Run>, Error> {
+        get_runs_all(client, &self.name).await
+    }
+
+    /// Gets the Runs that are associated with the Runner and match the query.
+    pub async fn query_runs(&self, client: &Client, query: &RunQuery) -> Result<Vec<Run>, Error> {
+        query_runs(client, &self.name, query).await
+    }
+
+    /// Gets the profile of the Runner, which combines the Runner with their personal bests,
+    /// games, categories and runs. The requests are made concurrently, with at most the given
+    /// number of requests at a time. A concurrency of 0 is treated as 1.
+    pub async fn profile(
+        &self,
+        client: &Client,
+        concurrency: usize,
+    ) -> Result<RunnerProfile, Error> {
+        get_profile(client, &self.name, concurrency).await
+    }
+
+    /// Streams the Runs that are associated with the Runner. The Runs are requested one page at a
+    /// time as they are needed.
+    pub fn runs_stream<'a>(
+        &self,
+        client: &'a Client,
+        settings: PageSettings,
+    ) -> impl Stream<Item = Result<Run, Error>> + 'a {
+        get_runs_stream(client, &self.name, settings)
+    }
+
     /// Gets the personal best Runs that are associated with the Runner.
     pub async fn pbs(&self, client: &Client) -> Result<Vec<Run>, Error> {
         get_pbs(client, &self.name).await
@@ -53,25 +89,69 @@ impl Runner {
 
 /// Searches for a Runner based on the name of the runner.
 pub async fn search(client: &Client, name: &str) -> Result<Vec<Runner>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
-    url.query_pairs_mut().append_pair("search", name);
-
-    let ContainsRunners { runners } = get_json(client, client.client.get(url)).await?;
+    let ContainsRunners { runners } =
+        get_json(client, client.client.get(search_url(client, name))).await?;
 
     Ok(runners)
 }
 
+/// Searches for a Runner based on the name of the runner. All the pages of the results are
+/// retrieved, one after another.
+pub async fn search_all(client: &Client, name: &str) -> Result<Vec<Runner>, Error> {
+    search_stream(client, name, PageSettings::default())
+        .try_collect()
+        .await
+}
+
+/// Gets a single page of the Runners that match the name of the runner.
+pub async fn search_page(
+    client: &Client,
+    name: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Runner>, Error> {
+    page::get_page(
+        client,
+        search_url(client, name),
+        page,
+        per_page,
+        |r: ContainsRunners| r.runners,
+    )
+    .await
+}
+
+/// Streams the Runners that match the name of the runner. The Runners are requested one page at a
+/// time as they are needed.
+pub fn search_stream<'a>(
+    client: &'a Client,
+    name: &str,
+    settings: PageSettings,
+) -> impl Stream<Item = Result<Runner, Error>> + 'a {
+    page::stream(
+        client,
+        search_url(client, name),
+        settings,
+        |r: ContainsRunners| r.runners,
+    )
+}
+
+fn search_url(client: &Client, name: &str) -> Url {
+    let mut url = client.api_url("runners");
+    url.query_pairs_mut().append_pair("search", name);
+    url
+}
+
 /// Gets the Runner that is associated with the current user.
 pub async fn myself(client: &Client) -> Result<Runner, Error> {
     let ContainsRunner { runner } =
-        get_json(client, client.client.get("https://splits.io/api/v4/runner")).await?;
+        get_json(client, client.client.get(client.api_url("runner"))).await?;
 
     Ok(runner)
 }
 
 /// Gets a Runner based on the name of the runner.
 pub async fn get(client: &Client, name: &str) -> Result<Runner, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
+    let mut url = client.api_url("runners");
     url.path_segments_mut().unwrap().push(name);
 
     let ContainsRunner { runner } = get_json(client, client.client.get(url)).await?;
@@ -81,17 +161,67 @@ pub async fn get(client: &Client, name: &str) -> Result<Runner, Error> {
 
 /// Gets the Runs that are associated with a Runner.
 pub async fn get_runs(client: &Client, name: &str) -> Result<Vec<Run>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
-    url.path_segments_mut().unwrap().extend(&[name, "runs"]);
-
-    let ContainsRuns { runs } = get_json(client, client.client.get(url)).await?;
+    let ContainsRuns { runs } = get_json(client, client.client.get(runs_url(client, name))).await?;
 
     Ok(runs)
 }
 
+/// Gets the Runs that are associated with a Runner. All the pages of the Runs are retrieved, one
+/// after another.
+pub async fn get_runs_all(client: &Client, name: &str) -> Result<Vec<Run>, Error> {
+    get_runs_stream(client, name, PageSettings::default())
+        .try_collect()
+        .await
+}
+
+/// Gets a single page of the Runs that are associated with a Runner.
+pub async fn get_runs_page(
+    client: &Client,
+    name: &str,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Run>, Error> {
+    page::get_page(
+        client,
+        runs_url(client, name),
+        page,
+        per_page,
+        |r: ContainsRuns| r.runs,
+    )
+    .await
+}
+
+/// Streams the Runs that are associated with a Runner. The Runs are requested one page at a time
+/// as they are needed.
+pub fn get_runs_stream<'a>(
+    client: &'a Client,
+    name: &str,
+    settings: PageSettings,
+) -> impl Stream<Item = Result<Run, Error>> + 'a {
+    page::stream(
+        client,
+        runs_url(client, name),
+        settings,
+        |r: ContainsRuns| r.runs,
+    )
+}
+
+/// Gets the Runs that are associated with a Runner and match the query.
+pub async fn query_runs(client: &Client, name: &str, query: &RunQuery) -> Result<Vec<Run>, Error> {
+    query
+        .collect(get_runs_stream(client, name, query.page_settings()))
+        .await
+}
+
+fn runs_url(client: &Client, name: &str) -> Url {
+    let mut url = client.api_url("runners");
+    url.path_segments_mut().unwrap().extend(&[name, "runs"]);
+    url
+}
+
 /// Gets the personal best Runs that are associated with a Runner.
 pub async fn get_pbs(client: &Client, name: &str) -> Result<Vec<Run>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
+    let mut url = client.api_url("runners");
     url.path_segments_mut().unwrap().extend(&[name, "pbs"]);
 
     let ContainsPBs { pbs } = get_json(client, client.client.get(url)).await?;
@@ -101,7 +231,7 @@ pub async fn get_pbs(client: &Client, name: &str) -> Result<Vec<Run>, Error> {
 
 /// Gets the Games that are associated with a Runner.
 pub async fn get_games(client: &Client, name: &str) -> Result<Vec<Game>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
+    let mut url = client.api_url("runners");
     url.path_segments_mut().unwrap().extend(&[name, "games"]);
 
     let ContainsGames { games } = get_json(client, client.client.get(url)).await?;
@@ -111,7 +241,7 @@ pub async fn get_games(client: &Client, name: &str) -> Result<Vec<Game>, Error>
 
 /// Gets the Categories that are associated with a Runner.
 pub async fn get_categories(client: &Client, name: &str) -> Result<Vec<Category>, Error> {
-    let mut url = Url::parse("https://splits.io/api/v4/runners").unwrap();
+    let mut url = client.api_url("runners");
     url.path_segments_mut()
         .unwrap()
         .extend(&[name, "categories"]);
@@ -120,3 +250,53 @@ pub async fn get_categories(client: &Client, name: &str) -> Result<Vec<Category>
 
     Ok(categories)
 }
+
+/// Gets the profile of a Runner, which combines the Runner with their personal bests, games,
+/// categories and runs. The requests are made concurrently, with at most the given number of
+/// requests at a time. A concurrency of 0 is treated as 1. The runs are retrieved without their
+/// history, see [`RunnerProfile::load_histories`].
+pub async fn get_profile(
+    client: &Client,
+    name: &str,
+    concurrency: usize,
+) -> Result<RunnerProfile, Error> {
+    enum Part {
+        Runner(Runner),
+        Pbs(Vec<Run>),
+        Games(Vec<Game>),
+        Categories(Vec<Category>),
+        Runs(Vec<Run>),
+    }
+
+    let mut parts = stream::iter(0..5)
+        .map(|part| async move {
+            Ok::<_, Error>(match part {
+                0 => Part::Runner(get(client, name).await?),
+                1 => Part::Pbs(get_pbs(client, name).await?),
+                2 => Part::Games(get_games(client, name).await?),
+                3 => Part::Categories(get_categories(client, name).await?),
+                _ => Part::Runs(get_runs_all(client, name).await?),
+            })
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let (mut runner, mut pbs, mut games, mut categories, mut runs) =
+        (None, Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    while let Some(part) = parts.try_next().await? {
+        match part {
+            Part::Runner(part) => runner = Some(part),
+            Part::Pbs(part) => pbs = part,
+            Part::Games(part) => games = part,
+            Part::Categories(part) => categories = part,
+            Part::Runs(part) => runs = part,
+        }
+    }
+
+    Ok(RunnerProfile {
+        runner: runner.ok_or(Error::UnidentifiableResource)?,
+        pbs,
+        games,
+        categories,
+        runs,
+    })
+}
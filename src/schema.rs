@@ -3,7 +3,7 @@ use uuid::Uuid;
 /// A Category is a ruleset for a Game (Any%, 100%, MST, etc.) and an optional container for Runs.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#category)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct Category {
     /// The time and date at which this category was created on splits.io. This field conforms to
     /// ISO 8601.
@@ -77,7 +77,7 @@ pub struct Entry {
 /// A Game is a collection of information about a game, and a container for Categories.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#game)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct Game {
     /// The known speedrun categories for this game.
     pub categories: Option<Vec<Category>>,
@@ -100,7 +100,7 @@ pub struct Game {
 /// Information about a past attempt associated with a Run.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#history)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct RunItemHistories {
     /// The corresponding attempt number this attempt was.
     pub attempt_number: u32,
@@ -113,7 +113,7 @@ pub struct RunItemHistories {
 /// A Run maps 1:1 to an uploaded splits file.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#run)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct Run {
     /// The number of run attempts recorded by the timer that generated the run's source file, if
     /// supported by the source timer.
@@ -178,7 +178,7 @@ pub struct Run {
 /// A Runner is a user who has at least one run tied to their account.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#runner)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct Runner {
     /// The avatar of the user.
     #[serde(default)]
@@ -204,7 +204,7 @@ pub struct Runner {
 /// Information about a past attempt of a segment.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#history)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct SegmentItemHistories {
     /// The corresponding attempt number this attempt was.
     pub attempt_number: u32,
@@ -217,7 +217,7 @@ pub struct SegmentItemHistories {
 /// A Segment maps to a single piece of a run, also called a split.
 ///
 /// [API Documentation](https://github.com/glacials/splits-io/blob/master/docs/api.md#segment)
-#[derive(Debug, serde_derive::Deserialize)]
+#[derive(Debug, Clone, serde_derive::Deserialize, serde_derive::Serialize)]
 pub struct Segment {
     /// Gametime duration in milliseconds of the segment.
     #[serde(default)]
@@ -0,0 +1,306 @@
+//! The search index module provides fuzzy searching for Games and Runners without making a request
+//! for every search. The index is filled with the results of searches on splits.io or with a
+//! crawl of the whole catalog and can be stored on disk by serializing it.
+//!
+//! ```no_run
+//! # use splits_io_api::{search_index::SearchIndex, Client};
+//! # async fn search(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let index = SearchIndex::crawl(client, 4).await?;
+//! std::fs::write("index.json", serde_json::to_vec(&index)?)?;
+//!
+//! for result in index.search_games("wind waker", 5) {
+//!     println!("{} ({:.2})", result.item.name, result.score);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{game, runner, Client, Error, Game, Runner};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+/// A result of searching the index.
+#[derive(Debug, Copy, Clone)]
+pub struct SearchResult<'a, T> {
+    /// The Game or Runner that matched.
+    pub item: &'a T,
+    /// How well the Game or Runner matched, with higher scores being better matches. Exact
+    /// matches score at least 2.
+    pub score: f64,
+}
+
+/// An index of Games and Runners that can be searched by their names.
+#[derive(Debug, Clone, Default, serde_derive::Deserialize, serde_derive::Serialize)]
+#[serde(from = "Entries", into = "Entries")]
+pub struct SearchIndex {
+    games: Catalog<Game>,
+    runners: Catalog<Runner>,
+}
+
+/// The contents of the index that are serialized. The trigrams are rebuilt when the index is
+/// deserialized.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize)]
+struct Entries {
+    games: Vec<Game>,
+    runners: Vec<Runner>,
+}
+
+impl From<Entries> for SearchIndex {
+    fn from(entries: Entries) -> Self {
+        let mut index = Self::new();
+        index.extend_games(entries.games);
+        index.extend_runners(entries.runners);
+        index
+    }
+}
+
+impl From<SearchIndex> for Entries {
+    fn from(index: SearchIndex) -> Self {
+        Self {
+            games: index.games.items,
+            runners: index.runners.items,
+        }
+    }
+}
+
+impl SearchIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crawls splits.io for all the Games and Runners, by searching for every letter and digit.
+    /// At most the given number of searches are made at a time. A concurrency of 0 is treated as
+    /// 1.
+    pub async fn crawl(client: &Client, concurrency: usize) -> Result<Self, Error> {
+        let mut index = Self::new();
+        let mut searches = stream::iter(('a'..='z').chain('0'..='9'))
+            .map(|c| async move {
+                let query = c.to_string();
+                let games = game::search_all(client, &query).await?;
+                let runners = runner::search_all(client, &query).await?;
+                Ok::<_, Error>((games, runners))
+            })
+            .buffer_unordered(concurrency.max(1));
+        while let Some((games, runners)) = searches.try_next().await? {
+            index.extend_games(games);
+            index.extend_runners(runners);
+        }
+        Ok(index)
+    }
+
+    /// Searches splits.io for Games and Runners matching the name and adds the first page of the
+    /// results to the index.
+    pub async fn add_search_results(&mut self, client: &Client, name: &str) -> Result<(), Error> {
+        self.extend_games(game::search(client, name).await?);
+        self.extend_runners(runner::search(client, name).await?);
+        Ok(())
+    }
+
+    /// Adds a Game to the index. A Game with the same ID is replaced.
+    pub fn insert_game(&mut self, game: Game) {
+        self.games.insert(game);
+    }
+
+    /// Adds Games to the index. Games with the same ID are replaced.
+    pub fn extend_games(&mut self, games: impl IntoIterator<Item = Game>) {
+        for game in games {
+            self.insert_game(game);
+        }
+    }
+
+    /// Adds a Runner to the index. A Runner with the same ID is replaced.
+    pub fn insert_runner(&mut self, runner: Runner) {
+        self.runners.insert(runner);
+    }
+
+    /// Adds Runners to the index. Runners with the same ID are replaced.
+    pub fn extend_runners(&mut self, runners: impl IntoIterator<Item = Runner>) {
+        for runner in runners {
+            self.insert_runner(runner);
+        }
+    }
+
+    /// All the Games in the index.
+    pub fn games(&self) -> &[Game] {
+        &self.games.items
+    }
+
+    /// All the Runners in the index.
+    pub fn runners(&self) -> &[Runner] {
+        &self.runners.items
+    }
+
+    /// Searches for Games by their name and shortened title. Returns at most the given number of
+    /// results, from the best match to the worst.
+    pub fn search_games(&self, query: &str, limit: usize) -> Vec<SearchResult<'_, Game>> {
+        self.games.search(query, limit)
+    }
+
+    /// Searches for Runners by their name and display name. Returns at most the given number of
+    /// results, from the best match to the worst.
+    pub fn search_runners(&self, query: &str, limit: usize) -> Vec<SearchResult<'_, Runner>> {
+        self.runners.search(query, limit)
+    }
+}
+
+/// A resource that can be searched for by its names.
+trait Searchable {
+    fn id(&self) -> &str;
+    fn names(&self) -> [Option<&str>; 2];
+}
+
+impl Searchable for Game {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn names(&self) -> [Option<&str>; 2] {
+        [Some(&self.name), self.shortname.as_deref()]
+    }
+}
+
+impl Searchable for Runner {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn names(&self) -> [Option<&str>; 2] {
+        [Some(&self.name), self.display_name.as_deref()]
+    }
+}
+
+type Trigram = [char; 3];
+
+#[derive(Debug, Clone)]
+struct Catalog<T> {
+    items: Vec<T>,
+    ids: HashMap<Box<str>, usize>,
+    trigrams: HashMap<Trigram, HashSet<usize>>,
+}
+
+impl<T> Default for Catalog<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            ids: HashMap::new(),
+            trigrams: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Searchable> Catalog<T> {
+    fn insert(&mut self, item: T) {
+        let index = match self.ids.get(item.id()) {
+            Some(&index) => {
+                let old = std::mem::replace(&mut self.items[index], item);
+                for trigram in item_trigrams(&old) {
+                    if let Entry::Occupied(mut postings) = self.trigrams.entry(trigram) {
+                        postings.get_mut().remove(&index);
+                        if postings.get().is_empty() {
+                            postings.remove();
+                        }
+                    }
+                }
+                index
+            }
+            None => {
+                let index = self.items.len();
+                self.ids.insert(item.id().into(), index);
+                self.items.push(item);
+                index
+            }
+        };
+
+        for trigram in item_trigrams(&self.items[index]) {
+            self.trigrams.entry(trigram).or_default().insert(index);
+        }
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Vec<SearchResult<'_, T>> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let query_trigrams = trigrams(&query);
+
+        let candidates = query_trigrams
+            .iter()
+            .filter_map(|trigram| self.trigrams.get(trigram))
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut results = candidates
+            .into_iter()
+            .filter_map(|index| {
+                let item = &self.items[index];
+                let score = item
+                    .names()
+                    .into_iter()
+                    .flatten()
+                    .map(|name| score(&query, &query_trigrams, &normalize(name)))
+                    .fold(0.0, f64::max);
+                (score >= MIN_SCORE).then_some(SearchResult { item, score })
+            })
+            .collect::<Vec<_>>();
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.item.names()[0].cmp(&b.item.names()[0]))
+        });
+        results.truncate(limit);
+        results
+    }
+}
+
+/// The minimum score of a result, so unrelated names that only share a trigram are left out.
+const MIN_SCORE: f64 = 0.2;
+
+/// Scores how well the name matches the query. The similarity of their trigrams is combined with
+/// a bonus for names that are equal to, start with or contain the query.
+fn score(query: &str, query_trigrams: &HashSet<Trigram>, name: &str) -> f64 {
+    let name_trigrams = trigrams(name);
+    let shared = query_trigrams.intersection(&name_trigrams).count();
+    let similarity = 2.0 * shared as f64 / (query_trigrams.len() + name_trigrams.len()) as f64;
+
+    let bonus = if name == query {
+        1.0
+    } else if name.starts_with(query) {
+        0.5
+    } else if name.contains(query) {
+        0.25
+    } else {
+        0.0
+    };
+    similarity + bonus
+}
+
+/// The trigrams of all the names of the item.
+fn item_trigrams(item: &impl Searchable) -> HashSet<Trigram> {
+    item.names()
+        .into_iter()
+        .flatten()
+        .flat_map(|name| trigrams(&normalize(name)))
+        .collect()
+}
+
+/// Lowercases the text and replaces everything that isn't a letter or digit with single spaces.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The trigrams of the normalized text, padded so that the start of the text and short texts have
+/// trigrams as well.
+fn trigrams(text: &str) -> HashSet<Trigram> {
+    let padded = format!("  {text} ").chars().collect::<Vec<_>>();
+    padded
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .collect()
+}
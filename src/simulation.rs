@@ -0,0 +1,187 @@
+//! The simulation module estimates the chances of a Run finishing within a certain time. It
+//! simulates many attempts by sampling each segment's duration and reset chance from its history.
+//!
+//! ```no_run
+//! # use splits_io_api::{simulation::{Settings, Start}, Client, Run, TimingMethod};
+//! # async fn simulate(client: &Client) -> Result<(), splits_io_api::Error> {
+//! let run = Run::get(client, "4cg", true).await?;
+//!
+//! // The runner is currently 2 segments into a run at 5 minutes.
+//! let settings = Settings {
+//!     start: Some(Start {
+//!         completed_segments: 2,
+//!         current_time_ms: 300_000.0,
+//!     }),
+//!     ..Settings::default()
+//! };
+//!
+//! if let Some(result) = run.simulate(TimingMethod::RealTime, &settings) {
+//!     let pb = run.realtime_duration_ms.unwrap_or_default();
+//!     println!("PB chance: {:.1}%", 100.0 * result.probability_below(pb));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{history, stats::Statistics, Run, TimingMethod};
+
+/// The point of a run from which the simulation continues, such as the current state of a live
+/// run.
+#[derive(Debug, Copy, Clone)]
+pub struct Start {
+    /// The number of segments that are already completed.
+    pub completed_segments: usize,
+    /// The time of the run in milliseconds when the last completed segment was finished.
+    pub current_time_ms: f64,
+}
+
+/// The settings for a simulation.
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    /// The seed for the random number generator. Simulations with the same seed and history
+    /// produce the same results.
+    pub seed: u64,
+    /// The number of attempts to simulate.
+    pub iterations: u32,
+    /// Where to start the simulated attempts. If this is `None`, they start at the beginning of
+    /// the run.
+    pub start: Option<Start>,
+    /// Whether simulated attempts may be reset, based on how often the segments were reset in the
+    /// past.
+    pub include_resets: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            iterations: 10_000,
+            start: None,
+            include_resets: true,
+        }
+    }
+}
+
+/// The outcome of simulating many attempts of a run.
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    iterations: u32,
+    resets: u32,
+    final_times_ms: Vec<f64>,
+}
+
+impl SimulationResult {
+    /// The number of attempts that were simulated.
+    pub const fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// The number of simulated attempts that were reset.
+    pub const fn resets(&self) -> u32 {
+        self.resets
+    }
+
+    /// The fraction of the simulated attempts that were reset.
+    pub fn reset_probability(&self) -> f64 {
+        if self.iterations == 0 {
+            0.0
+        } else {
+            self.resets as f64 / self.iterations as f64
+        }
+    }
+
+    /// The final times in milliseconds of all the simulated attempts that finished, sorted from
+    /// fastest to slowest.
+    pub fn final_times_ms(&self) -> &[f64] {
+        &self.final_times_ms
+    }
+
+    /// The fraction of all the simulated attempts that finished faster than the given time in
+    /// milliseconds. Attempts that were reset count as not finishing faster.
+    pub fn probability_below(&self, time_ms: f64) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+        let below = self.final_times_ms.partition_point(|&time| time < time_ms);
+        below as f64 / self.iterations as f64
+    }
+
+    /// The statistics of the final times of the simulated attempts that finished.
+    pub fn statistics(&self) -> Option<Statistics> {
+        Statistics::from_durations(self.final_times_ms.iter().copied())
+    }
+}
+
+impl Run {
+    /// Simulates attempts of the run for the given timing method by sampling from the history of
+    /// its segments. This requires the run to be retrieved with its history. Returns `None` if a
+    /// remaining segment has never been completed before, so it can't be sampled.
+    pub fn simulate(&self, method: TimingMethod, settings: &Settings) -> Option<SimulationResult> {
+        let attempts = history::attempts(self, method);
+        let funnel = self.funnel(method);
+        let start = settings.start.unwrap_or(Start {
+            completed_segments: 0,
+            current_time_ms: 0.0,
+        });
+
+        let segments = (start.completed_segments..self.segments.len())
+            .map(|index| {
+                let durations = attempts
+                    .iter()
+                    .filter_map(|attempt| attempt.segment_duration_ms(index))
+                    .collect::<Vec<_>>();
+                if durations.is_empty() {
+                    return None;
+                }
+                let reset_rate = if settings.include_resets {
+                    funnel.stages[index].reset_rate()
+                } else {
+                    0.0
+                };
+                Some((durations, reset_rate))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut rng = SplitMix64(settings.seed);
+        let mut resets = 0;
+        let mut final_times_ms = Vec::with_capacity(settings.iterations as usize);
+
+        'iterations: for _ in 0..settings.iterations {
+            let mut time_ms = start.current_time_ms;
+            for (durations, reset_rate) in &segments {
+                if rng.next_f64() < *reset_rate {
+                    resets += 1;
+                    continue 'iterations;
+                }
+                time_ms += durations[(rng.next_f64() * durations.len() as f64) as usize];
+            }
+            final_times_ms.push(time_ms);
+        }
+
+        final_times_ms.sort_unstable_by(f64::total_cmp);
+
+        Some(SimulationResult {
+            iterations: settings.iterations,
+            resets,
+            final_times_ms,
+        })
+    }
+}
+
+/// A small and fast seedable random number generator.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a number in the range `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
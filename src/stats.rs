@@ -0,0 +1,141 @@
+//! The stats module calculates statistics about the past attempts of Runs and their Segments.
+//! Attempts that were reset or skipped don't have a duration and are excluded from all the
+//! statistics.
+
+use crate::{Run, Segment, TimingMethod};
+
+/// Statistics about a set of durations, such as the past attempts of a segment.
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    samples: Box<[f64]>,
+    mean_ms: f64,
+    std_dev_ms: f64,
+}
+
+impl Statistics {
+    /// Calculates the statistics of the given durations in milliseconds. Durations that are zero,
+    /// negative or not finite are considered missing and are ignored. Returns `None` if there are
+    /// no durations left.
+    pub fn from_durations<I>(durations: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        let mut samples = durations
+            .into_iter()
+            .filter(|&duration| duration.is_finite() && duration > 0.0)
+            .collect::<Box<[f64]>>();
+
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_unstable_by(f64::total_cmp);
+
+        let count = samples.len() as f64;
+        let mean_ms = samples.iter().sum::<f64>() / count;
+        let std_dev_ms = if samples.len() > 1 {
+            let variance = samples
+                .iter()
+                .map(|&duration| (duration - mean_ms) * (duration - mean_ms))
+                .sum::<f64>()
+                / (count - 1.0);
+            variance.sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            samples,
+            mean_ms,
+            std_dev_ms,
+        })
+    }
+
+    /// The durations the statistics are based on in milliseconds, sorted from shortest to
+    /// longest.
+    pub fn samples(&self) -> &[f64] {
+        &self.samples
+    }
+
+    /// The number of durations the statistics are based on.
+    pub fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// The arithmetic mean of the durations in milliseconds.
+    pub const fn mean_ms(&self) -> f64 {
+        self.mean_ms
+    }
+
+    /// The median of the durations in milliseconds.
+    pub fn median_ms(&self) -> f64 {
+        self.percentile_ms(0.5)
+    }
+
+    /// The sample standard deviation of the durations in milliseconds. This is zero if there is
+    /// only a single duration.
+    pub const fn std_dev_ms(&self) -> f64 {
+        self.std_dev_ms
+    }
+
+    /// The shortest duration in milliseconds.
+    pub fn min_ms(&self) -> f64 {
+        self.samples[0]
+    }
+
+    /// The longest duration in milliseconds.
+    pub fn max_ms(&self) -> f64 {
+        self.samples[self.samples.len() - 1]
+    }
+
+    /// The duration in milliseconds below which the given fraction of the durations lie. The
+    /// percentile is specified as a value between 0 and 1 and values in between two durations
+    /// are linearly interpolated.
+    pub fn percentile_ms(&self, percentile: f64) -> f64 {
+        let index = percentile.clamp(0.0, 1.0) * (self.samples.len() - 1) as f64;
+        let lower = self.samples[index.floor() as usize];
+        let upper = self.samples[index.ceil() as usize];
+        lower + (upper - lower) * index.fract()
+    }
+
+    /// The consistency of the durations, measured as the coefficient of variation, which is the
+    /// standard deviation relative to the mean. Lower values are more consistent. A value of 0.05
+    /// means that the durations typically deviate by 5% from the mean.
+    pub fn consistency(&self) -> f64 {
+        self.std_dev_ms / self.mean_ms
+    }
+}
+
+impl Segment {
+    /// Calculates the statistics of all the past attempts of the segment for the given timing
+    /// method. Returns `None` if there is no history for the segment.
+    pub fn statistics(&self, method: TimingMethod) -> Option<Statistics> {
+        Statistics::from_durations(
+            self.histories
+                .iter()
+                .flatten()
+                .filter_map(|history| history.duration_ms(method)),
+        )
+    }
+}
+
+impl Run {
+    /// Calculates the statistics of all the finished attempts of the run for the given timing
+    /// method. Returns `None` if there is no history of any finished attempts.
+    pub fn statistics(&self, method: TimingMethod) -> Option<Statistics> {
+        Statistics::from_durations(
+            self.histories
+                .iter()
+                .flatten()
+                .filter_map(|history| history.duration_ms(method)),
+        )
+    }
+
+    /// Calculates the statistics of each segment of the run for the given timing method.
+    pub fn segment_statistics(&self, method: TimingMethod) -> Vec<Option<Statistics>> {
+        self.segments
+            .iter()
+            .map(|segment| segment.statistics(method))
+            .collect()
+    }
+}
@@ -0,0 +1,237 @@
+//! The sum of best module detects best segments that were most likely caused by a mistake, such
+//! as a split that was missed or pressed too late. These best segments make the sum of best
+//! unreachable, so a cleaned sum of best is calculated without them.
+
+use crate::{
+    history::{self, Attempt},
+    stats::Statistics,
+    timing::FormatTime,
+    Run, TimingMethod,
+};
+use std::fmt;
+
+/// The settings that control how aggressively best segments are considered suspicious.
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    /// A best segment needs to be faster than the segment's median by at least this fraction of
+    /// the median before its neighboring segments are inspected.
+    pub min_deviation: f64,
+    /// The fraction of the time saved on a best segment that a neighboring segment needs to have
+    /// lost in the same attempt for the time to be considered shifted between them.
+    pub compensation: f64,
+    /// A best segment that is faster than this fraction of the segment's median is always
+    /// considered implausible, such as when the split button was pressed twice.
+    pub implausible_ratio: f64,
+    /// The minimum number of past attempts a segment needs to have before any of its best
+    /// segments are judged.
+    pub min_samples: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            min_deviation: 0.2,
+            compensation: 0.5,
+            implausible_ratio: 0.25,
+            min_samples: 3,
+        }
+    }
+}
+
+/// The reason why a best segment is considered suspicious.
+#[derive(Debug, Clone)]
+pub enum Reason {
+    /// The time saved on the segment was lost on a neighboring segment in the same attempt, so the
+    /// split between them was most likely pressed at the wrong time.
+    TimeShifted {
+        /// The index of the neighboring segment within the run.
+        neighbor_segment_number: u32,
+        /// The name of the neighboring segment.
+        neighbor_name: Box<str>,
+        /// The duration of the neighboring segment in the same attempt in milliseconds.
+        neighbor_duration_ms: f64,
+        /// The median duration of the neighboring segment in milliseconds.
+        neighbor_median_ms: f64,
+    },
+    /// The segment is so much faster than usual that it can't be a real time, such as when the
+    /// split button was pressed twice.
+    ImplausiblyFast,
+}
+
+/// A best segment that was most likely caused by a mistake.
+#[derive(Debug, Clone)]
+pub struct SuspiciousGold {
+    /// The index of the segment within the run.
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub segment_name: Box<str>,
+    /// The attempt in which the best segment was achieved.
+    pub attempt_number: u32,
+    /// The duration of the best segment in milliseconds.
+    pub duration_ms: f64,
+    /// The median duration of the segment in milliseconds.
+    pub median_ms: f64,
+    /// Why the best segment is considered suspicious.
+    pub reason: Reason,
+}
+
+/// Explains why the best segment is considered suspicious.
+impl fmt::Display for SuspiciousGold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The best segment of {} on \u{201c}{}\u{201d} in attempt {} is {:.0}% faster than its \
+             median of {}",
+            FormatTime(self.duration_ms),
+            self.segment_name,
+            self.attempt_number,
+            100.0 * (1.0 - self.duration_ms / self.median_ms),
+            FormatTime(self.median_ms),
+        )?;
+        match &self.reason {
+            Reason::TimeShifted {
+                neighbor_name,
+                neighbor_duration_ms,
+                neighbor_median_ms,
+                ..
+            } => write!(
+                f,
+                ", while \u{201c}{}\u{201d} took {} in the same attempt, which is {} slower \
+                 than its median. The split between them was most likely missed or pressed at \
+                 the wrong time.",
+                neighbor_name,
+                FormatTime(*neighbor_duration_ms),
+                FormatTime(neighbor_duration_ms - neighbor_median_ms),
+            ),
+            Reason::ImplausiblyFast => f.write_str(
+                ". This is implausibly fast, so the split button was most likely pressed twice.",
+            ),
+        }
+    }
+}
+
+/// The result of cleaning the sum of best of a Run.
+#[derive(Debug, Clone)]
+pub struct SumOfBestReport {
+    /// All the best segments that are considered suspicious, ordered by segment. A segment may
+    /// have multiple suspicious best segments if several of its fastest times are suspicious.
+    pub suspicious_golds: Vec<SuspiciousGold>,
+    /// The fastest plausible duration of each segment in milliseconds. If every time of a segment
+    /// is suspicious, the slowest of them is used, as it is the least suspicious one.
+    pub cleaned_best_segments_ms: Vec<Option<f64>>,
+    /// The sum of the cleaned best segments in milliseconds. This is `None` if any segment doesn't
+    /// have a best segment.
+    pub cleaned_sum_of_best_ms: Option<f64>,
+}
+
+impl Run {
+    /// Detects the suspicious best segments of the run for the given timing method and calculates
+    /// the sum of best without them. This requires the run to be retrieved with its history.
+    /// Segments without any history keep their best segment as reported by splits.io.
+    pub fn clean_sum_of_best(&self, method: TimingMethod, settings: Settings) -> SumOfBestReport {
+        let attempts = history::attempts(self, method);
+        let medians = (0..self.segments.len())
+            .map(|index| {
+                let stats = Statistics::from_durations(
+                    attempts
+                        .iter()
+                        .filter_map(|attempt| attempt.segment_duration_ms(index)),
+                )?;
+                (stats.count() >= settings.min_samples).then(|| stats.median_ms())
+            })
+            .collect::<Vec<_>>();
+
+        let mut suspicious_golds = Vec::new();
+
+        let cleaned_best_segments_ms = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let mut candidates = attempts
+                    .iter()
+                    .filter_map(|attempt| Some((attempt, attempt.segment_duration_ms(index)?)))
+                    .collect::<Vec<_>>();
+
+                if candidates.is_empty() {
+                    return segment.shortest_duration_ms(method);
+                }
+
+                candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+                let Some(median_ms) = medians[index] else {
+                    return Some(candidates[0].1);
+                };
+
+                let least_suspicious_ms = candidates[candidates.len() - 1].1;
+
+                for (attempt, duration_ms) in candidates {
+                    match self.judge(attempt, index, duration_ms, median_ms, &medians, &settings) {
+                        Some(reason) => suspicious_golds.push(SuspiciousGold {
+                            segment_number: segment.segment_number,
+                            segment_name: segment.name.clone(),
+                            attempt_number: attempt.number,
+                            duration_ms,
+                            median_ms,
+                            reason,
+                        }),
+                        None => return Some(duration_ms),
+                    }
+                }
+
+                Some(least_suspicious_ms)
+            })
+            .collect::<Vec<_>>();
+
+        let cleaned_sum_of_best_ms = cleaned_best_segments_ms.iter().copied().sum();
+
+        SumOfBestReport {
+            suspicious_golds,
+            cleaned_best_segments_ms,
+            cleaned_sum_of_best_ms,
+        }
+    }
+
+    fn judge(
+        &self,
+        attempt: &Attempt,
+        index: usize,
+        duration_ms: f64,
+        median_ms: f64,
+        medians: &[Option<f64>],
+        settings: &Settings,
+    ) -> Option<Reason> {
+        if duration_ms < settings.implausible_ratio * median_ms {
+            return Some(Reason::ImplausiblyFast);
+        }
+
+        let saved_ms = median_ms - duration_ms;
+        if saved_ms < settings.min_deviation * median_ms {
+            return None;
+        }
+
+        [index.checked_sub(1), Some(index + 1)]
+            .into_iter()
+            .flatten()
+            .filter(|&neighbor| neighbor < attempt.segments.len())
+            .filter_map(|neighbor| {
+                let neighbor_duration_ms = attempt.segment_duration_ms(neighbor)?;
+                let neighbor_median_ms = medians[neighbor]?;
+                let lost_ms = neighbor_duration_ms - neighbor_median_ms;
+                (lost_ms >= settings.compensation * saved_ms).then(|| {
+                    let segment = &self.segments[neighbor];
+                    (
+                        lost_ms,
+                        Reason::TimeShifted {
+                            neighbor_segment_number: segment.segment_number,
+                            neighbor_name: segment.name.clone(),
+                            neighbor_duration_ms,
+                            neighbor_median_ms,
+                        },
+                    )
+                })
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, reason)| reason)
+    }
+}
@@ -0,0 +1,123 @@
+//! The time save module determines how much time could be saved on each segment of a Run by
+//! comparing it against the best segments, and what the best possible time of the run is.
+
+use crate::{Run, TimingMethod};
+
+/// The possible time save of a single segment.
+#[derive(Debug, Clone)]
+pub struct SegmentTimeSave {
+    /// The index of the segment within the run. (This value starts at 0.)
+    pub segment_number: u32,
+    /// The name of the segment.
+    pub name: Box<str>,
+    /// Whether the segment was skipped. The possible time save of a skipped segment is included
+    /// in the segment following it.
+    pub skipped: bool,
+    /// The number of skipped segments before this segment whose durations are included in this
+    /// segment's duration.
+    pub combined_segments: u32,
+    /// The duration of the segment in milliseconds, including the skipped segments before it.
+    pub duration_ms: Option<f64>,
+    /// The sum of the best segments in milliseconds that the duration covers.
+    pub best_ms: Option<f64>,
+    /// The time in milliseconds that could be saved on the segment. This is `None` for skipped
+    /// segments and segments that are missing a duration or best segment.
+    pub possible_time_save_ms: Option<f64>,
+}
+
+/// The possible time save of a Run.
+#[derive(Debug, Clone)]
+pub struct TimeSaveReport {
+    /// The timing method the report is for.
+    pub method: TimingMethod,
+    /// The possible time save of each segment.
+    pub segments: Vec<SegmentTimeSave>,
+    /// The sum of all the possible time saves in milliseconds.
+    pub total_possible_time_save_ms: f64,
+    /// The duration of the run in milliseconds, if all the possible time was saved.
+    pub best_possible_time_ms: Option<f64>,
+}
+
+impl TimeSaveReport {
+    /// The segments where the most time could be saved, ordered by their possible time save.
+    /// Segments without any possible time save are not included.
+    pub fn top(&self, n: usize) -> Vec<&SegmentTimeSave> {
+        let mut segments = self
+            .segments
+            .iter()
+            .filter(|segment| segment.possible_time_save_ms.is_some_and(|save| save > 0.0))
+            .collect::<Vec<_>>();
+        segments.sort_by(|a, b| {
+            b.possible_time_save_ms
+                .unwrap_or_default()
+                .total_cmp(&a.possible_time_save_ms.unwrap_or_default())
+        });
+        segments.truncate(n);
+        segments
+    }
+}
+
+impl Run {
+    /// Determines the possible time save of each segment of the run for the given timing method.
+    /// Skipped segments are combined with the reduced segment following them, so their durations
+    /// are compared against the sum of all the best segments they cover.
+    pub fn possible_time_save(&self, method: TimingMethod) -> TimeSaveReport {
+        let mut combined_segments = 0;
+        let mut combined_best_ms = Some(0.0);
+
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| {
+                let best_ms = combined_best_ms
+                    .zip(segment.shortest_duration_ms(method))
+                    .map(|(combined, best)| combined + best);
+
+                if segment.is_skipped(method) {
+                    combined_segments += 1;
+                    combined_best_ms = best_ms;
+                    return SegmentTimeSave {
+                        segment_number: segment.segment_number,
+                        name: segment.name.clone(),
+                        skipped: true,
+                        combined_segments: 0,
+                        duration_ms: None,
+                        best_ms: segment.shortest_duration_ms(method),
+                        possible_time_save_ms: None,
+                    };
+                }
+
+                let duration_ms = segment.duration_ms(method);
+                let time_save = SegmentTimeSave {
+                    segment_number: segment.segment_number,
+                    name: segment.name.clone(),
+                    skipped: false,
+                    combined_segments,
+                    duration_ms,
+                    best_ms,
+                    possible_time_save_ms: duration_ms
+                        .zip(best_ms)
+                        .map(|(duration, best)| (duration - best).max(0.0)),
+                };
+
+                combined_segments = 0;
+                combined_best_ms = Some(0.0);
+                time_save
+            })
+            .collect::<Vec<_>>();
+
+        let total_possible_time_save_ms = segments
+            .iter()
+            .filter_map(|segment| segment.possible_time_save_ms)
+            .sum::<f64>();
+
+        TimeSaveReport {
+            method,
+            best_possible_time_ms: self
+                .duration_ms(method)
+                .map(|duration| duration - total_possible_time_save_ms),
+            segments,
+            total_possible_time_save_ms,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{Run, RunItemHistories, Segment, SegmentItemHistories};
+use std::fmt;
+
+/// The timing method that is used to measure a run, either real time or game time.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, serde_derive::Deserialize, serde_derive::Serialize,
+)]
+pub enum TimingMethod {
+    /// Real time is the time that passed in reality.
+    #[serde(rename = "real")]
+    RealTime,
+    /// Game time is the time that is tracked by the game itself, usually excluding loading times.
+    #[serde(rename = "game")]
+    GameTime,
+}
+
+impl TimingMethod {
+    /// Both timing methods, real time first.
+    pub const ALL: [TimingMethod; 2] = [TimingMethod::RealTime, TimingMethod::GameTime];
+}
+
+impl Run {
+    /// Parses the default timing method of the run. Returns `None` if splits.io reported a timing
+    /// method that isn't known.
+    pub fn default_timing_method(&self) -> Option<TimingMethod> {
+        match &*self.default_timing {
+            "real" => Some(TimingMethod::RealTime),
+            "game" => Some(TimingMethod::GameTime),
+            _ => None,
+        }
+    }
+
+    /// The duration in milliseconds of the run for the given timing method.
+    pub const fn duration_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => self.realtime_duration_ms,
+            TimingMethod::GameTime => self.gametime_duration_ms,
+        }
+    }
+
+    /// The sum of best in milliseconds of the run for the given timing method.
+    pub const fn sum_of_best_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => self.realtime_sum_of_best_ms,
+            TimingMethod::GameTime => self.gametime_sum_of_best_ms,
+        }
+    }
+}
+
+impl Segment {
+    /// The duration in milliseconds of the segment for the given timing method.
+    pub const fn duration_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => Some(self.realtime_duration_ms),
+            TimingMethod::GameTime => self.gametime_duration_ms,
+        }
+    }
+
+    /// The total elapsed time of the run in milliseconds at the moment when this segment was
+    /// started for the given timing method.
+    pub const fn start_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => Some(self.realtime_start_ms),
+            TimingMethod::GameTime => self.gametime_start_ms,
+        }
+    }
+
+    /// The total elapsed time of the run in milliseconds at the moment when this segment was
+    /// finished for the given timing method.
+    pub const fn end_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => Some(self.realtime_end_ms),
+            TimingMethod::GameTime => self.gametime_end_ms,
+        }
+    }
+
+    /// The shortest duration in milliseconds the runner has ever gotten on this segment for the
+    /// given timing method.
+    pub const fn shortest_duration_ms(&self, method: TimingMethod) -> Option<f64> {
+        match method {
+            TimingMethod::RealTime => self.realtime_shortest_duration_ms,
+            TimingMethod::GameTime => self.gametime_shortest_duration_ms,
+        }
+    }
+
+    /// Whether or not this split was the shortest duration the runner has ever gotten on this
+    /// segment for the given timing method.
+    pub const fn is_gold(&self, method: TimingMethod) -> bool {
+        match method {
+            TimingMethod::RealTime => self.realtime_gold,
+            TimingMethod::GameTime => self.gametime_gold,
+        }
+    }
+
+    /// Whether or not this split was skipped for the given timing method.
+    pub const fn is_skipped(&self, method: TimingMethod) -> bool {
+        match method {
+            TimingMethod::RealTime => self.realtime_skipped,
+            TimingMethod::GameTime => self.gametime_skipped,
+        }
+    }
+
+    /// Whether or not this segment was "reduced" for the given timing method; that is, had its
+    /// duration affected by previous splits being skipped.
+    pub const fn is_reduced(&self, method: TimingMethod) -> bool {
+        match method {
+            TimingMethod::RealTime => self.realtime_reduced,
+            TimingMethod::GameTime => self.gametime_reduced,
+        }
+    }
+}
+
+impl RunItemHistories {
+    /// The duration in milliseconds this attempt took for the given timing method. Returns `None`
+    /// if the attempt wasn't finished, which timers record as a duration of zero.
+    pub fn duration_ms(&self, method: TimingMethod) -> Option<f64> {
+        valid_duration(match method {
+            TimingMethod::RealTime => self.realtime_duration_ms,
+            TimingMethod::GameTime => self.gametime_duration_ms,
+        })
+    }
+}
+
+impl SegmentItemHistories {
+    /// The duration in milliseconds this attempt took on the segment for the given timing method.
+    /// Returns `None` if the segment was skipped or the attempt was reset before finishing it,
+    /// which timers record as a duration of zero.
+    pub fn duration_ms(&self, method: TimingMethod) -> Option<f64> {
+        valid_duration(match method {
+            TimingMethod::RealTime => self.realtime_duration_ms,
+            TimingMethod::GameTime => self.gametime_duration_ms,
+        })
+    }
+}
+
+fn valid_duration(duration_ms: f64) -> Option<f64> {
+    (duration_ms.is_finite() && duration_ms > 0.0).then_some(duration_ms)
+}
+
+/// Formats a duration in milliseconds like a timer would, such as `1:02:03.456` or `-4.500`.
+pub(crate) struct FormatTime(pub(crate) f64);
+
+impl fmt::Display for FormatTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0.0 {
+            f.write_str("-")?;
+        }
+        let total_ms = self.0.abs().round() as u64;
+        let (hours, minutes) = (total_ms / 3_600_000, total_ms / 60_000 % 60);
+        let (seconds, millis) = (total_ms / 1000 % 60, total_ms % 1000);
+        if hours > 0 {
+            write!(f, "{hours}:{minutes:02}:{seconds:02}.{millis:03}")
+        } else if minutes > 0 {
+            write!(f, "{minutes}:{seconds:02}.{millis:03}")
+        } else {
+            write!(f, "{seconds}.{millis:03}")
+        }
+    }
+}
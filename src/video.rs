@@ -0,0 +1,236 @@
+//! The video module parses the video URLs of Runs, so that links can be created that jump to a
+//! specific segment of the run in the video.
+
+use crate::{Run, Segment, TimingMethod};
+use reqwest::Url;
+use std::fmt;
+
+/// A video on one of the platforms splits.io accepts as proof of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VideoLink {
+    /// A video on Twitch.
+    Twitch {
+        /// The ID of the video, without the `v` prefix some URLs use.
+        id: Box<str>,
+        /// The time in milliseconds within the video the link starts at.
+        start_ms: Option<f64>,
+    },
+    /// A video on YouTube.
+    YouTube {
+        /// The ID of the video.
+        id: Box<str>,
+        /// The time in milliseconds within the video the link starts at.
+        start_ms: Option<f64>,
+    },
+    /// A video on Hitbox, which is now known as Smashcast.
+    Hitbox {
+        /// The ID of the video.
+        id: Box<str>,
+        /// The time in milliseconds within the video the link starts at.
+        start_ms: Option<f64>,
+    },
+}
+
+impl VideoLink {
+    /// Parses a URL of a Twitch, YouTube or Hitbox video. The URL may be missing its scheme and
+    /// may link to the video through any of the ways these platforms support, such as embeds,
+    /// mobile pages and short links. Returns `None` if the URL isn't a video on one of the
+    /// platforms.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let url = Url::parse(url)
+            .ok()
+            .filter(|url| url.has_host())
+            .or_else(|| Url::parse(&format!("https://{url}")).ok())?;
+
+        let host = url.host_str()?.to_ascii_lowercase();
+        let host = host
+            .strip_prefix("www.")
+            .or_else(|| host.strip_prefix("m."))
+            .unwrap_or(&host);
+        let segments = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let query = |key: &str| {
+            url.query_pairs()
+                .find_map(|(k, v)| (k == key).then(|| v.into_owned()))
+        };
+        let fragment_time = url
+            .fragment()
+            .and_then(|fragment| fragment.strip_prefix("t="))
+            .and_then(parse_timestamp);
+
+        match host {
+            "twitch.tv" | "player.twitch.tv" => {
+                let id = match segments.as_slice() {
+                    ["videos", id, ..] => id.to_string(),
+                    [_, "v" | "video" | "videos", id, ..] => id.to_string(),
+                    [] => query("video")?,
+                    _ => return None,
+                };
+                let id = id.strip_prefix('v').unwrap_or(&id);
+                let start_ms = query("t")
+                    .as_deref()
+                    .or(query("time").as_deref())
+                    .and_then(parse_timestamp);
+                Some(VideoLink::Twitch {
+                    id: valid_id(id, |c| c.is_ascii_digit())?,
+                    start_ms,
+                })
+            }
+            "youtube.com" | "music.youtube.com" | "youtube-nocookie.com" | "youtu.be" => {
+                let id = match segments.as_slice() {
+                    [id] if host == "youtu.be" => id.to_string(),
+                    ["watch", ..] => query("v")?,
+                    ["embed" | "v" | "live" | "shorts" | "e", id, ..] => id.to_string(),
+                    _ => return None,
+                };
+                let start_ms = query("t")
+                    .as_deref()
+                    .or(query("start").as_deref())
+                    .and_then(parse_timestamp)
+                    .or(fragment_time);
+                Some(VideoLink::YouTube {
+                    id: valid_id(&id, |c| c.is_ascii_alphanumeric() || c == '-' || c == '_')?,
+                    start_ms,
+                })
+            }
+            "hitbox.tv" | "smashcast.tv" => {
+                let id = match segments.as_slice() {
+                    ["video", id, ..] | [_, "video", id, ..] => id,
+                    _ => return None,
+                };
+                Some(VideoLink::Hitbox {
+                    id: valid_id(id, |c| c.is_ascii_digit())?,
+                    start_ms: None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The ID of the video on its platform.
+    pub fn id(&self) -> &str {
+        match self {
+            VideoLink::Twitch { id, .. }
+            | VideoLink::YouTube { id, .. }
+            | VideoLink::Hitbox { id, .. } => id,
+        }
+    }
+
+    /// The time in milliseconds within the video the link starts at.
+    pub const fn start_ms(&self) -> Option<f64> {
+        match self {
+            VideoLink::Twitch { start_ms, .. }
+            | VideoLink::YouTube { start_ms, .. }
+            | VideoLink::Hitbox { start_ms, .. } => *start_ms,
+        }
+    }
+
+    /// Returns a link to the same video that starts at the given time in milliseconds.
+    pub fn with_start_ms(&self, time_ms: f64) -> Self {
+        let (id, start_ms) = (self.id().into(), Some(time_ms.max(0.0)));
+        match self {
+            VideoLink::Twitch { .. } => VideoLink::Twitch { id, start_ms },
+            VideoLink::YouTube { .. } => VideoLink::YouTube { id, start_ms },
+            VideoLink::Hitbox { .. } => VideoLink::Hitbox { id, start_ms },
+        }
+    }
+
+    /// Creates the canonical URL of the video. Hitbox doesn't support links to a specific time,
+    /// so the start time is not part of its URLs.
+    pub fn url(&self) -> Url {
+        let seconds = self.start_ms().map(|ms| (ms / 1000.0) as u64);
+        match self {
+            VideoLink::Twitch { id, .. } => {
+                let mut url = Url::parse("https://www.twitch.tv/videos").unwrap();
+                url.path_segments_mut().unwrap().push(id);
+                if let Some(seconds) = seconds {
+                    let t = format!("{}h{}m{}s", seconds / 3600, seconds / 60 % 60, seconds % 60);
+                    url.query_pairs_mut().append_pair("t", &t);
+                }
+                url
+            }
+            VideoLink::YouTube { id, .. } => {
+                let mut url = Url::parse("https://www.youtube.com/watch").unwrap();
+                url.query_pairs_mut().append_pair("v", id);
+                if let Some(seconds) = seconds {
+                    url.query_pairs_mut()
+                        .append_pair("t", &format!("{seconds}s"));
+                }
+                url
+            }
+            VideoLink::Hitbox { id, .. } => {
+                let mut url = Url::parse("https://www.smashcast.tv/video").unwrap();
+                url.path_segments_mut().unwrap().push(id);
+                url
+            }
+        }
+    }
+}
+
+impl fmt::Display for VideoLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.url(), f)
+    }
+}
+
+impl Run {
+    /// Parses the video URL of the run. Returns `None` if there is no video or the URL isn't a
+    /// video on a known platform.
+    pub fn video_link(&self) -> Option<VideoLink> {
+        VideoLink::parse(self.video_url.as_deref()?)
+    }
+}
+
+impl Segment {
+    /// Creates a link to the video of the run that starts where this segment starts. The offset
+    /// is the time in milliseconds within the video when the timer started. It is added to any
+    /// start time that is already part of the video URL, as runners often link to where the run
+    /// starts in a longer video. Returns `None` if the run has no video on a known platform.
+    pub fn video_link(&self, run: &Run, offset_ms: f64) -> Option<VideoLink> {
+        let link = run.video_link()?;
+        let start_ms = link.start_ms().unwrap_or_default()
+            + offset_ms
+            + self.start_ms(TimingMethod::RealTime).unwrap_or_default();
+        Some(link.with_start_ms(start_ms))
+    }
+}
+
+fn valid_id(id: &str, is_valid: impl Fn(char) -> bool) -> Option<Box<str>> {
+    (!id.is_empty() && id.chars().all(is_valid)).then(|| id.into())
+}
+
+/// Parses a timestamp such as `1h2m3s`, `90s`, `90` or `1:02:03` into milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.trim();
+    if timestamp.contains(':') {
+        let total_seconds = timestamp.split(':').try_fold(0.0, |total, part| {
+            Some(total * 60.0 + part.parse::<f64>().ok()?)
+        })?;
+        return (total_seconds >= 0.0).then_some(total_seconds * 1000.0);
+    }
+
+    let mut total_seconds = 0.0;
+    let mut number = String::new();
+    for c in timestamp.chars() {
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            c => {
+                number.push(c);
+                continue;
+            }
+        };
+        total_seconds += number.parse::<f64>().ok()? * unit;
+        number.clear();
+    }
+    if !number.is_empty() {
+        total_seconds += number.parse::<f64>().ok()?;
+    }
+
+    (total_seconds.is_finite() && total_seconds >= 0.0 && !timestamp.is_empty())
+        .then_some(total_seconds * 1000.0)
+}
@@ -0,0 +1,374 @@
+//! The watch module polls splits.io for new runs and personal bests of a Runner or Category. The
+//! runs that were already seen are recorded in a [`Snapshot`], which can be persisted to disk so
+//! that a restarted watcher doesn't announce them again. The watcher doesn't depend on an async
+//! runtime, so the function that waits between polls needs to be provided.
+//!
+//! The first poll of a new snapshot retrieves all the runs of the target to record them. Later
+//! polls only retrieve the first page of the runs, as long as it holds the most recent runs.
+//! If the page isn't ordered from newest to oldest, or every run on it is new, all the runs are
+//! retrieved instead, so no new run can be missed.
+//!
+//! ```no_run
+//! # use splits_io_api::{watch::{Settings, Target, WatchEvent, Watcher}, Client};
+//! # use futures_util::TryStreamExt;
+//! # async fn watch(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let settings = Settings {
+//!     snapshot: Some("snapshot.json".as_ref()),
+//!     ..Settings::default()
+//! };
+//! let watcher = Watcher::new(client, Target::Runner("cryze92".into()), settings)?;
+//!
+//! let mut events = std::pin::pin!(watcher.into_stream(tokio::time::sleep));
+//! while let Some(event) = events.try_next().await? {
+//!     if let WatchEvent::NewPb(run) = event {
+//!         println!("New PB: {:?}", run.id);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{category, page::Page, runner, Client, Error, Run, TimingMethod};
+use futures_util::stream::{self, Stream};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt, fs,
+    future::Future,
+    io,
+    path::Path,
+    time::Duration,
+};
+
+/// What to watch for new runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The runs of the Runner with the given name.
+    Runner(Box<str>),
+    /// The runs of the Category with the given ID.
+    Category(Box<str>),
+}
+
+/// A change that was noticed while watching.
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// A run was uploaded.
+    NewRun(Run),
+    /// A run is a new personal best. For a Category, this is a run that is faster than the
+    /// previous runs of its runners in the Category. The run is announced as a [`NewRun`] as well
+    /// if it was just uploaded.
+    ///
+    /// [`NewRun`]: WatchEvent::NewRun
+    NewPb(Run),
+    /// A run that was seen before was modified, such as by adding a video.
+    RunUpdated(Run),
+}
+
+/// An error while watching.
+#[derive(Debug)]
+pub enum WatchError {
+    /// Retrieving the runs from splits.io failed.
+    Poll {
+        /// The reason why retrieving the runs failed.
+        source: Error,
+    },
+    /// Storing the snapshot failed.
+    Snapshot {
+        /// The reason why storing the snapshot failed.
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::Poll { .. } => fmt::Display::fmt("Failed retrieving the runs.", fmt),
+            WatchError::Snapshot { .. } => fmt::Display::fmt("Failed storing the snapshot.", fmt),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WatchError::Poll { source } => Some(source),
+            WatchError::Snapshot { source } => Some(source),
+        }
+    }
+}
+
+/// What a watcher knows about a run it has seen.
+#[derive(Debug, Clone, PartialEq, Eq, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct SnapshotEntry {
+    /// When the run was last modified.
+    pub updated_at: Box<str>,
+    /// Whether the run was a personal best at some point. This stays set once the run was
+    /// recorded as a personal best, even if it was beaten since.
+    pub pb: bool,
+}
+
+/// The runs a watcher has seen, keyed by their ID.
+#[derive(Debug, Clone, Default, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct Snapshot {
+    /// Whether the watcher has polled before. The first poll only records the existing runs
+    /// without announcing them.
+    pub initialized: bool,
+    /// The runs that were seen, keyed by their ID.
+    pub runs: BTreeMap<Box<str>, SnapshotEntry>,
+    /// The duration in milliseconds of the fastest run seen of each runner, keyed by their ID.
+    /// This is only recorded when watching a Category.
+    #[serde(default)]
+    pub bests: BTreeMap<Box<str>, f64>,
+}
+
+impl Snapshot {
+    /// Loads a snapshot from the given path. If there is no file at the path yet, an empty
+    /// snapshot is returned.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::from),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stores the snapshot at the given path. The snapshot is first written to a temporary file
+    /// next to it, so an interruption never leaves a partially written snapshot behind.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&temp_path, path)
+    }
+
+    /// Compares the runs against the snapshot and records them. The IDs of the runs that are
+    /// personal bests are given separately. Runs that were recorded as personal bests before stay
+    /// recorded as such. On the first update, the runs are only recorded.
+    pub fn update(&mut self, runs: Vec<Run>, pbs: &HashSet<Box<str>>) -> Vec<WatchEvent> {
+        let announce = self.initialized;
+        self.initialized = true;
+
+        let mut events = Vec::new();
+        for run in runs {
+            let Some(id) = run.id.clone() else {
+                continue;
+            };
+            let previous = self.runs.get(&id).cloned();
+            let pb = pbs.contains(&id) || previous.as_ref().is_some_and(|previous| previous.pb);
+            let entry = SnapshotEntry {
+                updated_at: run.updated_at.clone(),
+                pb,
+            };
+            self.runs.insert(id, entry);
+            if !announce {
+                continue;
+            }
+            match previous {
+                None => {
+                    let new_pb = pb.then(|| WatchEvent::NewPb(run.clone()));
+                    events.push(WatchEvent::NewRun(run));
+                    events.extend(new_pb);
+                }
+                Some(previous) => {
+                    let new_pb = (pb && !previous.pb).then(|| WatchEvent::NewPb(run.clone()));
+                    if previous.updated_at != run.updated_at {
+                        events.push(WatchEvent::RunUpdated(run));
+                    }
+                    events.extend(new_pb);
+                }
+            }
+        }
+        events
+    }
+
+    /// Determines which of the runs of a Category are personal bests, which are the runs that are
+    /// faster than every run seen before of at least one of their runners. The fastest run of each
+    /// runner is recorded. Returns the IDs of the personal bests.
+    pub fn category_pbs(&mut self, runs: &[Run], method: TimingMethod) -> HashSet<Box<str>> {
+        let mut runs = runs
+            .iter()
+            .filter_map(|run| Some((run.id.as_ref()?, run.duration_ms(method)?, run)))
+            .filter(|&(_, duration_ms, _)| duration_ms > 0.0)
+            .collect::<Vec<_>>();
+        runs.sort_by(|(_, _, a), (_, _, b)| a.created_at.cmp(&b.created_at));
+
+        let mut pbs = HashSet::new();
+        for (id, duration_ms, run) in runs {
+            for runner in &run.runners {
+                let best = self.bests.get(&runner.id);
+                if best.is_none_or(|&best| duration_ms < best) {
+                    self.bests.insert(runner.id.clone(), duration_ms);
+                    pbs.insert(id.clone());
+                }
+            }
+        }
+        pbs
+    }
+}
+
+/// The settings for watching.
+#[derive(Debug, Clone)]
+pub struct Settings<'a> {
+    /// How long to wait between polls.
+    pub interval: Duration,
+    /// The timing method that determines the personal bests of a Category.
+    pub method: TimingMethod,
+    /// The path of the snapshot to resume from. It is updated after every poll that noticed a
+    /// change. If this is `None`, the watcher starts with an empty snapshot every time.
+    pub snapshot: Option<&'a Path>,
+}
+
+impl Default for Settings<'_> {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            method: TimingMethod::RealTime,
+            snapshot: None,
+        }
+    }
+}
+
+/// The number of runs that later polls request on the first page.
+const RECENT_RUNS: u32 = 100;
+
+/// Polls splits.io for changes to the runs of a Runner or Category.
+pub struct Watcher<'a> {
+    client: &'a Client,
+    target: Target,
+    settings: Settings<'a>,
+    snapshot: Snapshot,
+    unsaved: bool,
+}
+
+impl<'a> Watcher<'a> {
+    /// Creates a watcher for the target. The snapshot is loaded from the path in the settings if
+    /// there is one.
+    pub fn new(client: &'a Client, target: Target, settings: Settings<'a>) -> io::Result<Self> {
+        let snapshot = match settings.snapshot {
+            Some(path) => Snapshot::load(path)?,
+            None => Snapshot::default(),
+        };
+        Ok(Self {
+            client,
+            target,
+            settings,
+            snapshot,
+            unsaved: false,
+        })
+    }
+
+    /// The runs the watcher has seen so far.
+    pub const fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    /// Retrieves the runs of the target once and returns the changes since the last poll. The
+    /// first poll of a new snapshot only records the existing runs. The snapshot isn't stored, see
+    /// [`save`](Self::save).
+    pub async fn poll(&mut self) -> Result<Vec<WatchEvent>, Error> {
+        let initialized = self.snapshot.initialized;
+        let (runs, pbs) = match &self.target {
+            Target::Runner(name) => {
+                let mut runs = None;
+                if initialized {
+                    let page = runner::get_runs_page(self.client, name, 1, RECENT_RUNS).await?;
+                    runs = self.recent_runs(page);
+                }
+                let runs = match runs {
+                    Some(runs) => runs,
+                    None => runner::get_runs_all(self.client, name).await?,
+                };
+                let pbs = runner::get_pbs(self.client, name).await?;
+                (runs, pbs.into_iter().filter_map(|run| run.id).collect())
+            }
+            Target::Category(id) => {
+                let mut runs = None;
+                if initialized {
+                    let page = category::get_runs_page(self.client, id, 1, RECENT_RUNS).await?;
+                    runs = self.recent_runs(page);
+                }
+                let runs = match runs {
+                    Some(runs) => runs,
+                    None => category::get_runs_all(self.client, id).await?,
+                };
+                let pbs = self.snapshot.category_pbs(&runs, self.settings.method);
+                (runs, pbs)
+            }
+        };
+
+        let events = self.snapshot.update(runs, &pbs);
+        self.unsaved |= !initialized || !events.is_empty();
+        Ok(events)
+    }
+
+    /// The runs on the first page, if they are known to include every run that is new since the
+    /// last poll. That is the case if the page holds all the runs, or if it is ordered from newest
+    /// to oldest and holds a run that was seen before.
+    fn recent_runs(&self, page: Page<Run>) -> Option<Vec<Run>> {
+        if !page.has_next() {
+            return Some(page.items);
+        }
+        let newest_first = page
+            .items
+            .windows(2)
+            .all(|pair| pair[0].created_at >= pair[1].created_at);
+        let reaches_seen_runs = page.items.iter().any(|run| {
+            run.id
+                .as_ref()
+                .is_some_and(|id| self.snapshot.runs.contains_key(id))
+        });
+        (newest_first && reaches_seen_runs).then_some(page.items)
+    }
+
+    /// Stores the snapshot at the path in the settings, if there is one and the snapshot changed
+    /// since it was last stored.
+    pub fn save(&mut self) -> io::Result<()> {
+        if let (Some(path), true) = (self.settings.snapshot, self.unsaved) {
+            self.snapshot.save(path)?;
+        }
+        self.unsaved = false;
+        Ok(())
+    }
+
+    /// Turns the watcher into a stream of the changes it notices. The given function is called
+    /// with the interval to wait between polls, such as `tokio::time::sleep`. The snapshot is
+    /// stored after every poll that noticed a change. Errors are yielded without ending the
+    /// stream, so the next poll is tried after the interval.
+    pub fn into_stream<S, F>(
+        self,
+        sleep: S,
+    ) -> impl Stream<Item = Result<WatchEvent, WatchError>> + 'a
+    where
+        S: FnMut(Duration) -> F + 'a,
+        F: Future<Output = ()> + 'a,
+    {
+        let state = (self, sleep, VecDeque::new(), true);
+        stream::unfold(
+            state,
+            |(mut watcher, mut sleep, mut pending, first)| async move {
+                let mut first = first;
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((Ok(event), (watcher, sleep, pending, first)));
+                    }
+                    if !first {
+                        sleep(watcher.settings.interval).await;
+                    }
+                    first = false;
+                    let error = match watcher.poll().await {
+                        Ok(events) => {
+                            pending.extend(events);
+                            watcher
+                                .save()
+                                .err()
+                                .map(|source| WatchError::Snapshot { source })
+                        }
+                        Err(source) => Some(WatchError::Poll { source }),
+                    };
+                    if let Some(error) = error {
+                        return Some((Err(error), (watcher, sleep, pending, first)));
+                    }
+                }
+            },
+        )
+    }
+}
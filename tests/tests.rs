@@ -1,5 +1,18 @@
 use anyhow::{Context, Result};
-use splits_io_api::{Client, Run, Runner};
+use splits_io_api::{Client, Game, Run, Runner, TimingMethod};
+
+fn fixture_run() -> Run {
+    serde_json::from_str(include_str!("fixtures/run.json")).unwrap()
+}
+
+/// Creates an empty directory that is unique to the test, so tests running in parallel don't
+/// overwrite each other's files.
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("splits-io-api-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
 
 #[tokio::test]
 async fn can_query_run() -> Result<()> {
@@ -11,6 +24,39 @@ async fn can_query_run() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn can_stream_runs_by_page() -> Result<()> {
+    use futures_util::TryStreamExt;
+    use splits_io_api::{page::PageSettings, runner};
+
+    let client = Client::new();
+    let page = runner::get_runs_page(&client, "cryze92", 1, 2).await?;
+    assert!(page.items.len() <= 2);
+
+    let settings = PageSettings {
+        per_page: 2,
+        prefetch: true,
+    };
+    let runs: Vec<Run> = runner::get_runs_stream(&client, "cryze92", settings)
+        .try_collect()
+        .await?;
+    assert!(runs.len() >= page.items.len());
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_find_game_by_name_and_id() -> Result<()> {
+    let client = Client::new();
+    let game = Game::find_by_name(&client, "portal")
+        .await?
+        .context("No game with that name")?;
+    assert_eq!(&*game.name, "Portal");
+
+    let by_id = Game::get_by_id(&client, &game.id).await?;
+    assert_eq!(by_id.id, game.id);
+    Ok(())
+}
+
 #[tokio::test]
 async fn the_example_actually_works() -> Result<()> {
     // Create a splits.io API client.
@@ -59,3 +105,824 @@ async fn the_example_actually_works() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn bulk_manifest_round_trips() -> Result<()> {
+    use splits_io_api::bulk::{Manifest, ManifestEntry};
+    use std::path::Path;
+
+    let dir = test_dir("bulk-manifest");
+    let path = dir.join("manifest.json");
+    let mut manifest = Manifest::default();
+    manifest.entries.insert(
+        "a.lss".into(),
+        ManifestEntry::Uploaded {
+            id: "4cg".into(),
+            claim_token: "token".into(),
+        },
+    );
+    manifest.entries.insert(
+        "b.lss".into(),
+        ManifestEntry::Failed {
+            error: "HTTP Status: Bad Gateway".into(),
+        },
+    );
+    manifest.save(&path)?;
+
+    let loaded = Manifest::load(&path)?;
+    std::fs::remove_dir_all(&dir)?;
+
+    assert!(loaded.is_uploaded(Path::new("a.lss")));
+    assert!(!loaded.is_uploaded(Path::new("b.lss")));
+    assert_eq!(
+        loaded
+            .uploaded()
+            .next()
+            .context("No upload")?
+            .1
+            .claim_token
+            .as_ref(),
+        "token"
+    );
+    assert_eq!(loaded.failed().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn segment_statistics_exclude_resets() -> Result<()> {
+    let run = fixture_run();
+
+    let forest = run.segments[1]
+        .statistics(TimingMethod::RealTime)
+        .context("No statistics")?;
+    assert_eq!(forest.count(), 4);
+    assert_eq!(forest.min_ms(), 99000.0);
+    assert_eq!(forest.median_ms(), 101500.0);
+    assert_eq!(forest.mean_ms(), 101750.0);
+
+    let finished = run
+        .statistics(TimingMethod::GameTime)
+        .context("No statistics")?;
+    assert_eq!(finished.samples(), [287000.0, 297000.0]);
+    assert_eq!(finished.percentile_ms(0.25), 289500.0);
+    Ok(())
+}
+
+#[test]
+fn funnel_counts_resets_per_segment() {
+    let funnel = fixture_run().funnel(TimingMethod::RealTime);
+    assert_eq!(funnel.attempts, 6);
+
+    let counts = funnel
+        .stages
+        .iter()
+        .map(|stage| (stage.reached, stage.completed))
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [(6, 6), (6, 4), (4, 2)]);
+    assert_eq!(funnel.stages[2].reset_rate(), 0.5);
+}
+
+#[test]
+fn sum_of_best_cleaner_flags_shifted_gold() -> Result<()> {
+    use splits_io_api::sum_of_best::{Reason, Settings};
+
+    let mut run = fixture_run();
+    // Attempt 4 split late after the first segment, shifting 70 seconds into it.
+    let tower = run.segments[0].histories.as_mut().context("No history")?;
+    tower[3].realtime_duration_ms = 167000.0;
+    let forest = run.segments[1].histories.as_mut().context("No history")?;
+    forest[2].realtime_duration_ms = 31000.0;
+
+    let report = run.clean_sum_of_best(TimingMethod::RealTime, Settings::default());
+
+    assert_eq!(report.suspicious_golds.len(), 1);
+    let gold = &report.suspicious_golds[0];
+    assert_eq!((gold.segment_number, gold.attempt_number), (1, 4));
+    assert!(matches!(
+        gold.reason,
+        Reason::TimeShifted {
+            neighbor_segment_number: 0,
+            ..
+        }
+    ));
+    assert_eq!(report.cleaned_sum_of_best_ms, Some(289000.0));
+    Ok(())
+}
+
+#[test]
+fn comparisons_are_derived_from_history() {
+    use splits_io_api::comparisons::Comparison;
+
+    let run = fixture_run();
+    let comparison = |comparison| run.comparison(comparison, TimingMethod::RealTime);
+
+    assert_eq!(
+        comparison(Comparison::AverageSegments),
+        [Some(97500.0), Some(199250.0), Some(296750.0)],
+    );
+    assert_eq!(
+        comparison(Comparison::BestSplitTimes),
+        [Some(95000.0), Some(195000.0), Some(290000.0)],
+    );
+    assert_eq!(
+        comparison(Comparison::LatestRun),
+        [Some(99000.0), None, None]
+    );
+
+    let balanced_pb = comparison(Comparison::BalancedPb);
+    assert!((balanced_pb[2].unwrap() - 290000.0).abs() < 1e-6);
+
+    let segments = run.comparisons();
+    assert_eq!(segments[2].gametime.best_split_times_ms, Some(287000.0));
+}
+
+#[test]
+fn compare_matches_segments_by_name() {
+    let run = fixture_run();
+    let mut rival = fixture_run();
+    rival.segments.remove(1);
+    rival.segments[0].realtime_duration_ms -= 2000.0;
+    rival.segments[0].realtime_end_ms -= 2000.0;
+
+    let comparison = run.compare(&rival, TimingMethod::RealTime);
+    let other_segments = comparison
+        .segments
+        .iter()
+        .map(|delta| delta.other_segment_number)
+        .collect::<Vec<_>>();
+    assert_eq!(other_segments, [Some(0), None, Some(2)]);
+    assert_eq!(comparison.segments[0].segment_delta_ms, Some(2000.0));
+    assert_eq!(comparison.segments[0].cumulative_delta_ms, Some(2000.0));
+    assert_eq!(comparison.segments[1].segment_delta_ms, None);
+    assert_eq!(comparison.segments[2].cumulative_delta_ms, Some(0.0));
+    assert_eq!(comparison.final_delta_ms, Some(0.0));
+}
+
+#[test]
+fn aligner_matches_abbreviated_names() {
+    use splits_io_api::align::Aligner;
+
+    let run = fixture_run();
+    let mut rival = fixture_run();
+    rival.segments[0].name = "Dark Tower".into();
+    rival.segments[1].name = "Castle".into();
+    rival.segments[2].name = "Ganon Fight 2".into();
+
+    let mut abbreviated = fixture_run();
+    abbreviated.segments.truncate(1);
+    abbreviated.segments[0].name = "DT".into();
+
+    let alignment = Aligner::new(&[&rival, &run, &abbreviated]).align();
+    assert_eq!(alignment.matches(1), [Some(0), Some(2), None]);
+    assert_eq!(alignment.matches(2), [Some(0), None, None]);
+    assert_eq!(alignment.unmatched[1], [1]);
+
+    let corrected = Aligner::new(&[&rival, &run])
+        .without_match(1, 2)
+        .with_match(1, 1, 1)
+        .align();
+    assert_eq!(corrected.matches(1), [Some(0), Some(1), None]);
+    assert_eq!(corrected.rows[1].confidence, 1.0);
+}
+
+#[test]
+fn possible_time_save_combines_skipped_segments() {
+    let mut run = fixture_run();
+    let report = run.possible_time_save(TimingMethod::RealTime);
+    assert_eq!(report.total_possible_time_save_ms, 1000.0);
+    assert_eq!(report.best_possible_time_ms, Some(289000.0));
+    assert_eq!(&*report.top(3)[0].name, "Tower");
+
+    run.segments[1].realtime_skipped = true;
+    run.segments[1].realtime_duration_ms = 0.0;
+    run.segments[2].realtime_reduced = true;
+    run.segments[2].realtime_duration_ms = 196000.0;
+    let report = run.possible_time_save(TimingMethod::RealTime);
+    assert_eq!(report.segments[1].possible_time_save_ms, None);
+    assert_eq!(report.segments[2].combined_segments, 1);
+    assert_eq!(report.segments[2].possible_time_save_ms, Some(2000.0));
+    assert_eq!(report.top(1)[0].segment_number, 2);
+}
+
+#[test]
+fn simulation_samples_resets_and_durations() -> Result<()> {
+    use splits_io_api::simulation::{Settings, Start};
+
+    let run = fixture_run();
+    let settings = Settings::default();
+    let result = run
+        .simulate(TimingMethod::RealTime, &settings)
+        .context("Can't simulate")?;
+    assert!((result.reset_probability() - 2.0 / 3.0).abs() < 0.03);
+    assert!(result.final_times_ms()[0] >= 289000.0);
+
+    let again = run
+        .simulate(TimingMethod::RealTime, &settings)
+        .context("Can't simulate")?;
+    assert_eq!(result.final_times_ms(), again.final_times_ms());
+
+    let live = run
+        .simulate(
+            TimingMethod::RealTime,
+            &Settings {
+                start: Some(Start {
+                    completed_segments: 2,
+                    current_time_ms: 190000.0,
+                }),
+                include_resets: false,
+                ..settings
+            },
+        )
+        .context("Can't simulate")?;
+    assert_eq!(live.resets(), 0);
+    assert!((live.probability_below(287500.0) - 0.5).abs() < 0.03);
+    Ok(())
+}
+
+#[test]
+fn pace_tracker_compares_live_splits() {
+    use splits_io_api::pace::{PaceTracker, SplitStatus};
+
+    let run = fixture_run();
+    let mut tracker = PaceTracker::new(&run, TimingMethod::RealTime);
+
+    let first = tracker.split(94000.0);
+    assert_eq!(first.delta_ms, Some(-2000.0));
+    assert!(first.gold);
+    assert_eq!(first.status, SplitStatus::Gold);
+
+    assert_eq!(tracker.skip_split().status, SplitStatus::Skipped);
+    assert_eq!(tracker.live_segment_delta_ms(290000.0), Some(2000.0));
+
+    let last = tracker.split(291000.0);
+    assert_eq!(last.segment_time_ms, Some(197000.0));
+    assert_eq!(last.segment_delta_ms, Some(3000.0));
+    assert_eq!(last.status, SplitStatus::BehindLosing);
+    assert_eq!(tracker.projected_final_time_ms(), Some(291000.0));
+    assert!(tracker.is_finished());
+}
+
+#[test]
+fn pb_progression_and_gold_timeline() {
+    use splits_io_api::progression::PersonalBest;
+
+    let run = fixture_run();
+    assert_eq!(
+        run.pb_progression(TimingMethod::RealTime),
+        [
+            PersonalBest {
+                attempt_number: 3,
+                time_ms: 300000.0,
+                time_saved_ms: None,
+            },
+            PersonalBest {
+                attempt_number: 5,
+                time_ms: 290000.0,
+                time_saved_ms: Some(10000.0),
+            },
+        ],
+    );
+
+    let timeline = run.gold_timeline(TimingMethod::RealTime);
+    let events = timeline
+        .iter()
+        .map(|gold| (gold.attempt_number, gold.segment_number))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            (1, 0),
+            (2, 0),
+            (2, 1),
+            (3, 1),
+            (3, 2),
+            (4, 1),
+            (5, 1),
+            (5, 2)
+        ],
+    );
+    assert_eq!(timeline[1].time_saved_ms, Some(5000.0));
+}
+
+#[test]
+fn export_formats() -> Result<()> {
+    use splits_io_api::export;
+
+    let run = fixture_run();
+
+    let mut segments = Vec::new();
+    export::write_segments_csv(&run, &mut segments)?;
+    let segments = String::from_utf8(segments)?;
+    assert_eq!(
+        segments.lines().nth(2),
+        Some("1,Forest,195000,99000,99000,true,false,false,193000,98000,98000,true,false,false"),
+    );
+
+    let mut history = Vec::new();
+    export::write_history_csv(&run, &mut history)?;
+    let history = String::from_utf8(history)?;
+    assert_eq!(history.lines().count(), 7);
+    assert_eq!(
+        history.lines().next(),
+        Some(
+            "attempt_number,realtime_duration_ms,gametime_duration_ms,realtime_Tower,\
+             realtime_Forest,realtime_Castle,gametime_Tower,gametime_Forest,gametime_Castle"
+        ),
+    );
+    assert_eq!(history.lines().nth(1), Some("1,,,100000,,,99000,,"));
+
+    let mut json = Vec::new();
+    export::write_json_lines([&run, &run], &mut json)?;
+    let runs = json
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(serde_json::from_slice::<Run>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(runs.len(), 2);
+
+    let mut markdown = Vec::new();
+    export::write_markdown(&run, &mut markdown)?;
+    let markdown = String::from_utf8(markdown)?;
+    assert!(markdown.starts_with("# Example Game \u{2014} Any%\n"));
+    assert!(markdown.contains("- **Sum of Best:** 4:49.000\n"));
+    assert!(markdown.contains("| 1 | Tower | 1:36.000 | 1:36.000 | 1:35.000 | 1.000 |\n"));
+
+    let mut run = run;
+    run.segments[1].realtime_skipped = true;
+    let mut segments = Vec::new();
+    export::write_segments_csv(&run, &mut segments)?;
+    let segments = String::from_utf8(segments)?;
+    assert_eq!(
+        segments.lines().nth(2),
+        Some("1,Forest,,,99000,true,true,false,193000,98000,98000,true,false,false"),
+    );
+    Ok(())
+}
+
+#[test]
+fn lss_contains_history() {
+    let mut run = fixture_run();
+    run.game.as_mut().unwrap().name = "Tom & Jerry".into();
+    let lss = splits_io_api::lss::to_string(&run);
+
+    assert!(lss.contains("<GameName>Tom &amp; Jerry</GameName>"));
+    assert!(lss.contains("<AttemptCount>6</AttemptCount>"));
+    assert!(lss.contains(
+        "    <Attempt id=\"5\">\n      <RealTime>00:04:50.0000000</RealTime>\n      \
+         <GameTime>00:04:47.0000000</GameTime>\n    </Attempt>\n"
+    ));
+    assert!(lss.contains("    <Attempt id=\"6\" />\n"));
+    assert!(
+        lss.contains("      <BestSegmentTime>\n        <RealTime>00:01:39.0000000</RealTime>\n")
+    );
+    assert_eq!(lss.matches("<Time id=").count(), 12);
+}
+
+#[test]
+fn chapters_and_subtitles() {
+    use splits_io_api::chapters;
+
+    let run = fixture_run();
+
+    let mut youtube = Vec::new();
+    chapters::write_youtube_chapters(&run, 42_000.0, &mut youtube).unwrap();
+    assert_eq!(
+        String::from_utf8(youtube).unwrap(),
+        "0:00 Intro\n0:42 Tower\n2:18 Forest\n3:57 Castle\n",
+    );
+
+    let mut vtt = Vec::new();
+    chapters::write_webvtt(&run, -100_000.0, &mut vtt).unwrap();
+    assert_eq!(
+        String::from_utf8(vtt).unwrap(),
+        "WEBVTT\n\n00:00:00.000 --> 00:01:35.000\nForest\n\n\
+         00:01:35.000 --> 00:03:10.000\nCastle\n",
+    );
+
+    let mut srt = Vec::new();
+    chapters::write_srt(&run, 500.0, &mut srt).unwrap();
+    assert!(String::from_utf8(srt)
+        .unwrap()
+        .starts_with("1\n00:00:00,500 --> 00:01:36,500\nTower\n\n2\n"));
+}
+
+#[test]
+fn video_links_are_parsed() {
+    use splits_io_api::video::VideoLink;
+
+    for (url, id, start_ms) in [
+        ("https://www.twitch.tv/videos/123456789", "123456789", None),
+        ("twitch.tv/runner/v/123?t=1h2m3s", "123", Some(3_723_000.0)),
+        (
+            "https://player.twitch.tv/?video=v123&time=90s",
+            "123",
+            Some(90_000.0),
+        ),
+        (
+            "https://youtu.be/dQw4w9WgXcQ?t=42",
+            "dQw4w9WgXcQ",
+            Some(42_000.0),
+        ),
+        (
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ#t=1:30",
+            "dQw4w9WgXcQ",
+            Some(90_000.0),
+        ),
+        (
+            "https://www.youtube.com/embed/dQw4w9WgXcQ?start=5",
+            "dQw4w9WgXcQ",
+            Some(5_000.0),
+        ),
+        ("http://www.hitbox.tv/video/456", "456", None),
+    ] {
+        let link = VideoLink::parse(url).unwrap();
+        assert_eq!((link.id(), link.start_ms()), (id, start_ms), "{url}");
+    }
+    assert!(VideoLink::parse("https://www.twitch.tv/runner").is_none());
+    assert!(VideoLink::parse("https://example.com/videos/1").is_none());
+
+    let run = fixture_run();
+    let link = run.segments[1].video_link(&run, 30_000.0).unwrap();
+    assert!(matches!(link, VideoLink::Twitch { .. }));
+    assert_eq!(
+        link.to_string(),
+        "https://www.twitch.tv/videos/123456789?t=0h2m6s",
+    );
+}
+
+#[test]
+fn run_query_filters_and_sorts() {
+    use splits_io_api::query::{RunQuery, SortBy};
+
+    let runs = || {
+        let mut slow = fixture_run();
+        slow.realtime_duration_ms = Some(300_000.0);
+        slow.created_at = "2020-06-01T00:00:00.000Z".into();
+        let mut other = fixture_run();
+        other.game = None;
+        other.video_url = None;
+        [fixture_run(), slow, other]
+    };
+
+    let query = RunQuery::new().game("eg").category("any%").has_video(true);
+    assert_eq!(query.apply(runs()).len(), 2);
+
+    let fastest = RunQuery::new()
+        .sort_by(SortBy::Duration(TimingMethod::RealTime))
+        .limit(1)
+        .apply(runs());
+    assert_eq!(fastest[0].realtime_duration_ms, Some(290_000.0));
+
+    let newest = RunQuery::new()
+        .created_after("2020-01-01")
+        .sort_by_descending(SortBy::CreatedAt)
+        .apply(runs());
+    assert_eq!(newest.len(), 1);
+    assert_eq!(newest[0].realtime_duration_ms, Some(300_000.0));
+
+    assert!(RunQuery::new().program("wsplit").apply(runs()).is_empty());
+}
+
+#[test]
+fn leaderboard_ranks_fastest_run_per_runner() {
+    use splits_io_api::leaderboard::Leaderboard;
+
+    let run = |runner: Option<&str>, duration_ms: f64| {
+        let mut run = fixture_run();
+        match runner {
+            Some(name) => {
+                run.runners[0].id = name.into();
+                run.runners[0].name = name.into();
+            }
+            None => run.runners.clear(),
+        }
+        run.realtime_duration_ms = Some(duration_ms);
+        run
+    };
+    let mut duo = run(Some("a"), 280_000.0);
+    duo.runners.extend(run(Some("b"), 0.0).runners);
+
+    let leaderboard = Leaderboard::from_runs(
+        [
+            run(Some("a"), 300_000.0),
+            run(Some("A"), 290_000.0),
+            run(Some("b"), 290_000.0),
+            run(Some("c"), 295_000.0),
+            run(None, 100_000.0),
+            duo,
+        ],
+        TimingMethod::RealTime,
+    );
+
+    let ranks = leaderboard
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.rank,
+                &*entry.runner.name,
+                entry.runners().len(),
+                entry.gap_ms,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranks,
+        [
+            (1, "a", 2, 0.0),
+            (1, "b", 2, 0.0),
+            (3, "A", 1, 10_000.0),
+            (4, "c", 1, 15_000.0),
+        ],
+    );
+    assert_eq!(leaderboard.find_runner("A").unwrap().rank, 3);
+    assert_eq!(leaderboard.find_runner("C").unwrap().rank, 4);
+}
+
+#[test]
+fn runner_profile_links_pbs_and_totals() {
+    use splits_io_api::profile::RunnerProfile;
+
+    let mut run = fixture_run();
+    let profile = RunnerProfile {
+        runner: run.runners.pop().unwrap(),
+        games: run.game.take().into_iter().collect(),
+        categories: run.category.take().into_iter().collect(),
+        pbs: vec![fixture_run()],
+        runs: vec![fixture_run(), fixture_run()],
+    };
+
+    let pb = profile.linked_pbs().next().unwrap();
+    assert_eq!(&*pb.game.unwrap().name, "Example Game");
+    assert_eq!(&*pb.category.unwrap().name, "Any%");
+    assert_eq!(profile.total_attempts(), 12);
+    assert_eq!(profile.estimated_playtime_ms(), 2.0 * 1_187_000.0);
+}
+
+#[tokio::test]
+async fn complete_resources_hydrate_without_requests() -> Result<()> {
+    use splits_io_api::hydrate::Hydrate;
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    let mut client = Client::new();
+    client.set_cache_capacity(16);
+    let run = fixture_run();
+    let hydrated = assert_send(run.hydrate(&client)).await?;
+    assert_eq!(hydrated.segments.len(), run.segments.len());
+
+    let mut unidentifiable = fixture_run();
+    unidentifiable.id = None;
+    unidentifiable.histories = None;
+    assert!(unidentifiable.hydrate(&client).await.is_err());
+
+    assert!(None::<Run>.hydrate(&client).await?.is_none());
+    Ok(())
+}
+
+#[test]
+fn resources_are_parsed_from_urls() {
+    use splits_io_api::resource::Resource;
+
+    let race_id = "0a1b2c3d-4e5f-6789-abcd-ef0123456789";
+    for (link, resource) in [
+        ("https://splits.io/4cg", Resource::Run("4cg".into())),
+        (
+            "splits.io/users/cryze92",
+            Resource::Runner("cryze92".into()),
+        ),
+        (
+            "https://www.splits.io/games/tww",
+            Resource::Game("tww".into()),
+        ),
+        (
+            "https://splits.io/games/tww/123",
+            Resource::Category {
+                game: "tww".into(),
+                id: "123".into(),
+            },
+        ),
+        (
+            &format!("/races/{race_id}?join_token=secret"),
+            Resource::Race {
+                id: race_id.parse().unwrap(),
+                join_token: Some("secret".into()),
+            },
+        ),
+    ] {
+        assert_eq!(Resource::parse(link).as_ref(), Some(&resource), "{link}");
+        assert_eq!(Resource::from_url(&resource.url()), Some(resource));
+    }
+    assert_eq!(Resource::parse("https://splits.io/upload"), None);
+    assert_eq!(Resource::parse("https://splits.io/subscriptions"), None);
+    assert_eq!(Resource::parse("https://splits.io/robots.txt"), None);
+    assert_eq!(Resource::parse("https://example.com/4cg"), None);
+
+    let base = "http://localhost:3000/splits".parse().unwrap();
+    let run = Resource::Run("abc".into());
+    let url = run.url_with_base(&base).unwrap();
+    assert_eq!(url.as_str(), "http://localhost:3000/splits/abc");
+    assert_eq!(Resource::from_url_with_base(&url, &base), Some(run.clone()));
+    assert_eq!(Resource::from_url(&url), None);
+
+    let client = Client::with_base_url(base).unwrap();
+    assert_eq!(run.url_with_base(client.base_url()), Some(url.clone()));
+
+    let uploaded = splits_io_api::run::UploadedRun {
+        id: "abc".into(),
+        claim_token: "token".into(),
+    };
+    assert_eq!(uploaded.public_url_for(&client), url);
+    assert_eq!(
+        uploaded.claim_url_for(&client).as_str(),
+        "http://localhost:3000/splits/abc?claim_token=token",
+    );
+    assert_eq!(uploaded.public_url().as_str(), "https://splits.io/abc");
+
+    let mailto = "mailto:someone@example.com".parse().unwrap();
+    assert_eq!(run.url_with_base(&mailto), None);
+    assert!(Client::with_base_url(mailto).is_none());
+}
+
+#[test]
+fn search_index_ranks_fuzzy_matches() {
+    use splits_io_api::search_index::SearchIndex;
+
+    let game = |id: &str, name: &str, shortname: &str| {
+        let mut game = fixture_run().game.unwrap();
+        game.id = id.into();
+        game.name = name.into();
+        game.shortname = Some(shortname.into());
+        game
+    };
+
+    let mut index = SearchIndex::new();
+    index.extend_games([
+        game("1", "The Legend of Zelda: The Wind Waker", "tww"),
+        game("2", "The Legend of Zelda: Twilight Princess", "tp"),
+        game("3", "Super Mario Sunshine", "sms"),
+    ]);
+    index.insert_runner(fixture_run().runners.pop().unwrap());
+
+    let results = index.search_games("wind wakr", 10);
+    assert_eq!(&*results[0].item.id, "1");
+    assert!(results.iter().all(|result| &*result.item.id != "3"));
+
+    let results = index.search_games("SMS", 1);
+    assert_eq!(&*results[0].item.name, "Super Mario Sunshine");
+    assert!(results[0].score >= 2.0);
+
+    index.insert_game(game("3", "Super Mario Sunshine", "sms"));
+    index.insert_game(game("3", "Super Mario Sunshine", "sms"));
+    assert_eq!(index.search_games("mario sunshine", 10).len(), 1);
+
+    index.insert_game(game("2", "Twilight Princess HD", "tphd"));
+    assert_eq!(index.search_games("legend of zelda", 10).len(), 1);
+
+    let json = serde_json::to_string(&index).unwrap();
+    let index: SearchIndex = serde_json::from_str(&json).unwrap();
+    assert_eq!(index.games().len(), 3);
+    assert_eq!(&*index.search_runners("runer", 1)[0].item.name, "runner");
+}
+
+#[test]
+fn category_names_group_aliases() {
+    use splits_io_api::category_names::CategoryNames;
+
+    let mut run = fixture_run();
+    let game = run.game.take().unwrap();
+    let category = run.category.take().unwrap();
+    let categories = [
+        "Any%",
+        "any%",
+        "Any %",
+        "Any% (No SRM)",
+        "100%",
+        "Any Percent",
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(id, name)| {
+        let mut category = category.clone();
+        category.id = id.to_string().into();
+        category.name = name.into();
+        category
+    })
+    .collect::<Vec<_>>();
+
+    let groups = CategoryNames::new().group(&game, categories.clone());
+    let sizes = groups
+        .iter()
+        .map(|group| (group.name.as_str(), group.categories.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, [("Any%", 4), ("Any% (No SRM)", 1), ("100%", 1)]);
+
+    let names = CategoryNames::new()
+        .game_alias("eg", "Any% (No SRM)", "Any% No SRM")
+        .alias("any% no srm", "Any%");
+    assert!(names.same_category(Some(&game), "any% (no srm)", "Any% No SRM"));
+    assert!(!names.same_category(None, "Any% (No SRM)", "Any%"));
+
+    let names = CategoryNames::new()
+        .alias("any%", "Glitchless")
+        .alias("Any %", "Glitched")
+        .game_alias("13", "ANY%", "No SRM");
+    assert_eq!(names.key(None, "Any%"), "glitched");
+    assert_eq!(names.key(Some(&game), "Any%"), "nosrm");
+
+    let names: CategoryNames =
+        serde_json::from_str(r#"{ "game_aliases": { "13": { "Any% (No SRM)": "Any%" } } }"#)
+            .unwrap();
+    let groups = names.group(&game, categories);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(
+        groups[0].ids().collect::<Vec<_>>(),
+        ["0", "1", "2", "3", "5"]
+    );
+}
+
+#[test]
+fn watch_snapshot_announces_changes() {
+    use splits_io_api::watch::{Snapshot, WatchEvent};
+    use std::collections::HashSet;
+
+    let mut run = fixture_run();
+    run.id = Some("a".into());
+    let mut snapshot = Snapshot::default();
+    assert!(snapshot
+        .update(vec![run.clone()], &HashSet::new())
+        .is_empty());
+    assert!(snapshot
+        .update(vec![run.clone()], &HashSet::new())
+        .is_empty());
+
+    run.updated_at = "2019-05-03T12:00:00.000Z".into();
+    let mut pb = fixture_run();
+    pb.id = Some("b".into());
+    let pbs = HashSet::from(["b".into()]);
+    let events = snapshot.update(vec![run, pb], &pbs);
+    let kinds = events
+        .iter()
+        .map(|event| match event {
+            WatchEvent::NewRun(run) => ("new", run.id.as_deref()),
+            WatchEvent::NewPb(run) => ("pb", run.id.as_deref()),
+            WatchEvent::RunUpdated(run) => ("updated", run.id.as_deref()),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ("updated", Some("a")),
+            ("new", Some("b")),
+            ("pb", Some("b"))
+        ]
+    );
+
+    let mut pb = fixture_run();
+    pb.id = Some("b".into());
+    assert!(snapshot.update(vec![pb], &HashSet::new()).is_empty());
+    assert!(snapshot.runs["b"].pb);
+
+    let dir = test_dir("watch-snapshot");
+    let path = dir.join("snapshot.json");
+    snapshot.save(&path).unwrap();
+    let loaded = Snapshot::load(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(loaded.initialized);
+    assert_eq!(loaded.runs, snapshot.runs);
+}
+
+#[test]
+fn watch_snapshot_tracks_category_pbs() {
+    use splits_io_api::watch::Snapshot;
+
+    let run = |id: &str, runner: &str, duration_ms: f64| {
+        let mut run = fixture_run();
+        run.id = Some(id.into());
+        run.runners[0].id = runner.into();
+        run.realtime_duration_ms = Some(duration_ms);
+        run
+    };
+
+    let mut snapshot = Snapshot::default();
+    let pbs = snapshot.category_pbs(
+        &[run("a", "1", 300_000.0), run("b", "2", 310_000.0)],
+        TimingMethod::RealTime,
+    );
+    assert_eq!(pbs.len(), 2);
+
+    let pbs = snapshot.category_pbs(
+        &[
+            run("c", "1", 305_000.0),
+            run("d", "2", 305_000.0),
+            run("e", "3", 400_000.0),
+        ],
+        TimingMethod::RealTime,
+    );
+    let mut pbs = pbs.into_iter().collect::<Vec<_>>();
+    pbs.sort_unstable();
+    assert_eq!(pbs, [Box::from("d"), Box::from("e")]);
+    assert_eq!(snapshot.bests.get("1"), Some(&300_000.0));
+}